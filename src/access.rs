@@ -0,0 +1,128 @@
+use soroban_sdk::{contractimpl, contracttype, Address, Env};
+
+use crate::config::{self, GameConfig};
+use crate::errors::AccessError;
+use crate::events;
+use crate::metals;
+use crate::ttl::{self, TtlConfig};
+use crate::{GameContract, GameContractArgs, GameContractClient, StorageKey};
+
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Role {
+    Admin,        // single address, moved with transfer_admin
+    GameOperator, // contracts and services allowed to update game state
+    Moderator,    // may moderate player profiles
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DataKey {
+    Admin,
+    Role(Role, Address),
+}
+
+/// true if `account` holds `role`, the admin implicitly holds every role
+pub(crate) fn has_role(env: &Env, account: &Address, role: Role) -> bool {
+    let admin: Option<Address> = ttl::get_instance(env, &StorageKey::Access(DataKey::Admin));
+    if admin.as_ref() == Some(account) {
+        return true;
+    }
+    role != Role::Admin && ttl::has_instance(env, &StorageKey::Access(DataKey::Role(role, account.clone())))
+}
+
+/// checks the role and the caller's signature
+pub(crate) fn require_role(env: &Env, caller: &Address, role: Role) -> Result<(), AccessError> {
+    if !has_role(env, caller, role) {
+        return Err(AccessError::Unauthorized);
+    }
+    caller.require_auth();
+    Ok(())
+}
+
+#[contractimpl]
+impl GameContract {
+    /// set admin at deploy time
+    pub fn __constructor(env: Env, admin: Address) {
+        ttl::set_instance(&env, &StorageKey::Access(DataKey::Admin), &admin);
+        metals::seed(&env);
+    }
+
+    /// get admin address
+    pub fn get_admin(env: Env) -> Address {
+        ttl::get_instance(&env, &StorageKey::Access(DataKey::Admin)).unwrap()
+    }
+
+    /// check if an address holds a role
+    pub fn has_role(env: Env, account: Address, role: Role) -> bool {
+        has_role(&env, &account, role)
+    }
+
+    /// grant game operator or moderator role
+    pub fn grant_role(env: Env, admin: Address, account: Address, role: Role) -> Result<(), AccessError> {
+        require_role(&env, &admin, Role::Admin)?;
+        if role == Role::Admin {
+            return Err(AccessError::InvalidRole);
+        }
+
+        ttl::set_instance(&env, &StorageKey::Access(DataKey::Role(role, account.clone())), &true);
+        events::role_granted(&env, &account, role);
+        Ok(())
+    }
+
+    /// revoke game operator or moderator role
+    pub fn revoke_role(env: Env, admin: Address, account: Address, role: Role) -> Result<(), AccessError> {
+        require_role(&env, &admin, Role::Admin)?;
+        if role == Role::Admin {
+            return Err(AccessError::InvalidRole);
+        }
+
+        ttl::remove_instance(&env, &StorageKey::Access(DataKey::Role(role, account.clone())));
+        events::role_revoked(&env, &account, role);
+        Ok(())
+    }
+
+    /// hand the admin role to a new address, both sides have to sign
+    pub fn transfer_admin(env: Env, admin: Address, new_admin: Address) -> Result<(), AccessError> {
+        require_role(&env, &admin, Role::Admin)?;
+        new_admin.require_auth();
+
+        ttl::set_instance(&env, &StorageKey::Access(DataKey::Admin), &new_admin);
+        events::admin_transferred(&env, &admin, &new_admin);
+        Ok(())
+    }
+
+    /// get storage ttl settings
+    pub fn get_ttl_config(env: Env) -> TtlConfig {
+        ttl::config(&env)
+    }
+
+    /// change storage ttl settings, admin only
+    pub fn set_ttl_config(env: Env, admin: Address, config: TtlConfig) -> Result<(), AccessError> {
+        require_role(&env, &admin, Role::Admin)?;
+        if !config.is_valid(&env) {
+            return Err(AccessError::InvalidTtlConfig);
+        }
+
+        ttl::set_config(&env, &config);
+        events::ttl_config_set(&env, &admin, &config);
+        Ok(())
+    }
+
+    /// get economy parameters
+    pub fn get_game_config(env: Env) -> GameConfig {
+        config::game_config(&env)
+    }
+
+    /// change economy parameters, admin only
+    pub fn set_game_config(env: Env, admin: Address, config: GameConfig) -> Result<(), AccessError> {
+        require_role(&env, &admin, Role::Admin)?;
+        if !config.is_valid() {
+            return Err(AccessError::InvalidGameConfig);
+        }
+
+        config::set_game_config(&env, &config);
+        events::game_config_set(&env, &admin, &config);
+        Ok(())
+    }
+}
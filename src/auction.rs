@@ -0,0 +1,309 @@
+use soroban_sdk::{contractimpl, contracttype, token::TokenClient, Address, Env, Vec};
+
+use crate::errors::AuctionError;
+use crate::events;
+use crate::marketplace::{self, MarketConfig};
+use crate::mining::{self, MineLock};
+use crate::ttl;
+use crate::{GameContract, GameContractArgs, GameContractClient, StorageKey};
+
+// a new english bid has to beat the highest one by this much, in basis points
+pub const MIN_BID_INCREMENT_BPS: u32 = 500;
+
+// a bid this close to the end pushes the end back to now + window
+pub const EXTENSION_WINDOW: u64 = 300;
+
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum AuctionKind {
+    English, // ascending bids, highest bid wins at end_time
+    Dutch,   // price falls from start_price to reserve_price, first taker wins
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Auction {
+    pub id: u32,
+    pub seller: Address,
+    pub mine_id: u32,
+    pub kind: AuctionKind,
+    pub start_price: i128,   // dutch only, price at start_time
+    pub reserve_price: i128, // lowest price the mine sells for
+    pub start_time: u64,
+    pub end_time: u64,
+    pub highest_bidder: Option<Address>,
+    pub highest_bid: i128, // escrowed by the contract until outbid or settled
+    pub terms: MarketConfig, // payment token, treasury and fee at start time
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DataKey {
+    Auction(u32),
+    AuctionCount,
+    Refund(Address, Address), // bidder, payment token
+}
+
+// counters live in instance storage, auctions and refunds in persistent storage
+fn load_auction(env: &Env, auction_id: u32) -> Option<Auction> {
+    ttl::get_persistent(env, &StorageKey::Auction(DataKey::Auction(auction_id)))
+}
+
+fn save_auction(env: &Env, auction: &Auction) {
+    ttl::set_persistent(env, &StorageKey::Auction(DataKey::Auction(auction.id)), auction);
+}
+
+fn close_auction(env: &Env, auction_id: u32) {
+    env.storage()
+        .persistent()
+        .remove(&StorageKey::Auction(DataKey::Auction(auction_id)));
+}
+
+fn refund(env: &Env, bidder: &Address, token: &Address) -> i128 {
+    ttl::get_persistent(env, &StorageKey::Auction(DataKey::Refund(bidder.clone(), token.clone())))
+        .unwrap_or(0)
+}
+
+fn set_refund(env: &Env, bidder: &Address, token: &Address, amount: i128) {
+    ttl::set_persistent(
+        env,
+        &StorageKey::Auction(DataKey::Refund(bidder.clone(), token.clone())),
+        &amount,
+    );
+}
+
+fn config(env: &Env) -> Result<MarketConfig, AuctionError> {
+    marketplace::config(env).map_err(|_| AuctionError::NotConfigured)
+}
+
+// price at `now`, a dutch price falls linearly from start_price to reserve_price
+fn current_price(auction: &Auction, now: u64) -> i128 {
+    match auction.kind {
+        AuctionKind::English => auction.reserve_price.max(auction.highest_bid),
+        AuctionKind::Dutch => {
+            if now >= auction.end_time {
+                return auction.reserve_price;
+            }
+            let elapsed = now.saturating_sub(auction.start_time) as i128;
+            let duration = (auction.end_time - auction.start_time) as i128;
+            let drop = auction.start_price - auction.reserve_price;
+            auction.start_price - drop * elapsed / duration
+        }
+    }
+}
+
+// smallest bid that is accepted right now
+fn minimum_bid(auction: &Auction, now: u64) -> i128 {
+    match (&auction.kind, &auction.highest_bidder) {
+        (AuctionKind::English, Some(_)) => {
+            let increment = auction.highest_bid * MIN_BID_INCREMENT_BPS as i128 / 10_000;
+            auction.highest_bid + increment.max(1)
+        }
+        _ => current_price(auction, now),
+    }
+}
+
+// hand the locked mine to `to`, or back to the seller when nobody won
+fn release_mine(env: &Env, auction: &Auction, to: &Address) -> Result<(), AuctionError> {
+    mining::unlock_mine(env, auction.mine_id);
+    let mut mine = mining::load_mine(env, auction.mine_id).ok_or(AuctionError::MineNotFound)?;
+    if mine.owner != *to {
+        mining::change_owner(env, &mut mine, to).map_err(|_| AuctionError::TransferFailed)?;
+    }
+    Ok(())
+}
+
+#[contractimpl]
+impl GameContract {
+    /// put a mine up for auction, the mine is held in escrow until the
+    /// auction is settled or cancelled. Dutch auctions need a start price
+    /// above the reserve, english auctions ignore it
+    pub fn start_auction(
+        env: Env,
+        mine_id: u32,
+        kind: AuctionKind,
+        start_price: i128,
+        reserve_price: i128,
+        end_time: u64,
+    ) -> Result<u32, AuctionError> {
+        let terms = config(&env)?;
+        let mine = mining::load_mine(&env, mine_id).ok_or(AuctionError::MineNotFound)?;
+        mine.owner.require_auth();
+
+        if reserve_price <= 0 || (kind == AuctionKind::Dutch && start_price <= reserve_price) {
+            return Err(AuctionError::InvalidPrice);
+        }
+        let now = env.ledger().timestamp();
+        if end_time <= now {
+            return Err(AuctionError::InvalidEndTime);
+        }
+        if mining::mine_lock(&env, mine_id).is_some() {
+            return Err(AuctionError::MineLocked);
+        }
+
+        let id = ttl::get_instance::<u32>(&env, &StorageKey::Auction(DataKey::AuctionCount))
+            .unwrap_or(0)
+            + 1;
+        ttl::set_instance(&env, &StorageKey::Auction(DataKey::AuctionCount), &id);
+        mining::lock_mine(&env, mine_id, MineLock::Auction(id)).map_err(|_| AuctionError::MineLocked)?;
+
+        let auction = Auction {
+            id,
+            seller: mine.owner,
+            mine_id,
+            kind,
+            start_price: if kind == AuctionKind::Dutch { start_price } else { 0 },
+            reserve_price,
+            start_time: now,
+            end_time,
+            highest_bidder: None,
+            highest_bid: 0,
+            terms,
+        };
+        save_auction(&env, &auction);
+
+        events::auction_started(&env, &auction);
+        Ok(id)
+    }
+
+    /// bid on an auction. English bids are escrowed and become claimable with
+    /// claim_refund once outbid, a bid in the last minutes extends the
+    /// auction. A dutch bid at or above the current price buys the mine at
+    /// that price right away
+    pub fn place_bid(env: Env, bidder: Address, auction_id: u32, amount: i128) -> Result<(), AuctionError> {
+        bidder.require_auth();
+
+        let mut auction = load_auction(&env, auction_id).ok_or(AuctionError::AuctionNotFound)?;
+        let now = env.ledger().timestamp();
+        if now >= auction.end_time {
+            return Err(AuctionError::AuctionEnded);
+        }
+        if auction.seller == bidder {
+            return Err(AuctionError::SelfBid);
+        }
+        if amount < minimum_bid(&auction, now) {
+            return Err(AuctionError::BidTooLow);
+        }
+        if !mining::is_registered(&env, &bidder).map_err(|_| AuctionError::TransferFailed)? {
+            return Err(AuctionError::NotRegistered);
+        }
+
+        match auction.kind {
+            AuctionKind::English => {
+                let token = auction.terms.payment_token.clone();
+                let payment = TokenClient::new(&env, &token);
+                if payment.balance(&bidder) < amount {
+                    return Err(AuctionError::InsufficientFunds);
+                }
+
+                // the outbid bidder pulls the refund, so a bidder that can not
+                // receive the token can not block later bids
+                payment.transfer(&bidder, &env.current_contract_address(), &amount);
+                if let Some(previous) = auction.highest_bidder.clone() {
+                    let owed = refund(&env, &previous, &token) + auction.highest_bid;
+                    set_refund(&env, &previous, &token, owed);
+                    events::bid_refunded(&env, auction_id, &previous, auction.highest_bid);
+                }
+
+                auction.highest_bidder = Some(bidder.clone());
+                auction.highest_bid = amount;
+                if auction.end_time - now < EXTENSION_WINDOW {
+                    auction.end_time = now + EXTENSION_WINDOW;
+                }
+                save_auction(&env, &auction);
+                events::bid_placed(&env, &auction, &bidder, amount);
+            }
+            AuctionKind::Dutch => {
+                let price = current_price(&auction, now);
+                auction.highest_bidder = Some(bidder.clone());
+                auction.highest_bid = price;
+                events::bid_placed(&env, &auction, &bidder, price);
+
+                close_auction(&env, auction_id);
+                marketplace::settle_payment(&env, &auction.terms, &bidder, &auction.seller, price)
+                    .map_err(|_| AuctionError::InsufficientFunds)?;
+                release_mine(&env, &auction, &bidder)?;
+                events::auction_settled(&env, &auction);
+            }
+        }
+        Ok(())
+    }
+
+    /// close an auction after its end time, anyone can call. The highest
+    /// bidder gets the mine and the seller the bid minus the fee set at start,
+    /// without bids the mine goes back to the seller
+    pub fn settle_auction(env: Env, auction_id: u32) -> Result<(), AuctionError> {
+        let auction = load_auction(&env, auction_id).ok_or(AuctionError::AuctionNotFound)?;
+        if env.ledger().timestamp() < auction.end_time {
+            return Err(AuctionError::AuctionNotEnded);
+        }
+
+        close_auction(&env, auction_id);
+        match auction.highest_bidder.clone() {
+            Some(winner) => {
+                let this = env.current_contract_address();
+                marketplace::settle_payment(&env, &auction.terms, &this, &auction.seller, auction.highest_bid)
+                    .map_err(|_| AuctionError::InsufficientFunds)?;
+                release_mine(&env, &auction, &winner)?;
+            }
+            None => release_mine(&env, &auction, &auction.seller)?,
+        }
+        events::auction_settled(&env, &auction);
+        Ok(())
+    }
+
+    /// call off an auction nobody has bid on and return the mine
+    pub fn cancel_auction(env: Env, auction_id: u32) -> Result<(), AuctionError> {
+        let auction = load_auction(&env, auction_id).ok_or(AuctionError::AuctionNotFound)?;
+        auction.seller.require_auth();
+        if auction.highest_bidder.is_some() {
+            return Err(AuctionError::HasBids);
+        }
+
+        close_auction(&env, auction_id);
+        release_mine(&env, &auction, &auction.seller)?;
+        events::auction_cancelled(&env, &auction);
+        Ok(())
+    }
+
+    /// get auction data
+    pub fn get_auction(env: Env, auction_id: u32) -> Option<Auction> {
+        load_auction(&env, auction_id)
+    }
+
+    /// get the smallest bid an auction accepts right now
+    pub fn get_minimum_bid(env: Env, auction_id: u32) -> Result<i128, AuctionError> {
+        let auction = load_auction(&env, auction_id).ok_or(AuctionError::AuctionNotFound)?;
+        Ok(minimum_bid(&auction, env.ledger().timestamp()))
+    }
+
+    /// get outbid amounts waiting to be claimed by a bidder in a payment token
+    pub fn get_refund(env: Env, bidder: Address, token: Address) -> i128 {
+        refund(&env, &bidder, &token)
+    }
+
+    /// claim outbid amounts in a payment token, returns the amount
+    pub fn claim_refund(env: Env, bidder: Address, token: Address) -> Result<i128, AuctionError> {
+        bidder.require_auth();
+
+        let amount = refund(&env, &bidder, &token);
+        if amount == 0 {
+            return Err(AuctionError::NothingToClaim);
+        }
+        set_refund(&env, &bidder, &token, 0);
+        TokenClient::new(&env, &token).transfer(&env.current_contract_address(), &bidder, &amount);
+
+        events::refund_claimed(&env, &bidder, &token, amount);
+        Ok(amount)
+    }
+
+    /// get the open auctions among `limit` ids from `start_id` on, oldest first
+    pub fn get_auctions(env: Env, start_id: u32, limit: u32) -> Vec<Auction> {
+        marketplace::page_ids(&env, start_id, limit, load_auction)
+    }
+
+    /// get number of auctions ever started, ids run from 1 to this
+    pub fn get_auction_count(env: Env) -> u32 {
+        ttl::get_instance(&env, &StorageKey::Auction(DataKey::AuctionCount)).unwrap_or(0)
+    }
+}
@@ -0,0 +1,58 @@
+use soroban_sdk::{contracttype, Env};
+
+use crate::ttl;
+use crate::StorageKey;
+
+const HOUR: u64 = 60 * 60;
+const DAY: u64 = 24 * HOUR;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GameConfig {
+    pub harvest_cooldown: u64,         // seconds between two harvests, at least one accrual hour
+    pub max_upgrade_level: u32,        // cap for metals that do not set their own
+    pub upgrade_efficiency_bonus: u32, // efficiency points added per upgrade
+    pub upgrade_capacity_bps: u32,     // share of the metal's base capacity added per upgrade
+    pub experience_per_level: u64,
+    pub activity_window: u64, // seconds a player counts as active after a move
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DataKey {
+    Game,
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        GameConfig {
+            harvest_cooldown: HOUR,
+            max_upgrade_level: 10,
+            upgrade_efficiency_bonus: 5,
+            upgrade_capacity_bps: 1_000,
+            experience_per_level: 1_000,
+            activity_window: DAY,
+        }
+    }
+}
+
+impl GameConfig {
+    pub fn is_valid(&self) -> bool {
+        (HOUR..=7 * DAY).contains(&self.harvest_cooldown)
+            && (1..=100).contains(&self.max_upgrade_level)
+            && self.upgrade_efficiency_bonus <= 100
+            && self.upgrade_capacity_bps <= 10_000
+            && self.experience_per_level > 0
+            && (HOUR..=30 * DAY).contains(&self.activity_window)
+    }
+}
+
+// every instance keeps its own copy, the player instance reads the experience
+// and activity settings and the mining instance the rest
+pub(crate) fn game_config(env: &Env) -> GameConfig {
+    ttl::get_instance(env, &StorageKey::Config(DataKey::Game)).unwrap_or_default()
+}
+
+pub(crate) fn set_game_config(env: &Env, config: &GameConfig) {
+    ttl::set_instance(env, &StorageKey::Config(DataKey::Game), config);
+}
@@ -0,0 +1,284 @@
+use soroban_sdk::{contractimpl, contracttype, Address, Env, IntoVal, String, TryFromVal, Val, Vec};
+
+use crate::access::{self, Role};
+use crate::errors::CraftingError;
+use crate::events;
+use crate::metals;
+use crate::mining::MetalType;
+use crate::token;
+use crate::ttl;
+use crate::{GameContract, GameContractArgs, GameContractClient, StorageKey};
+
+// Crafted materials (ingots, alloys, ...) are registered by the admin and held
+// in their own balance ledger next to the metal tokens.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Material {
+    pub id: u32,
+    pub name: String,
+    pub symbol: String,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Resource {
+    Metal(MetalType),
+    Material(u32),
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ResourceAmount {
+    pub resource: Resource,
+    pub amount: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Recipe {
+    pub id: u32,
+    pub name: String,
+    pub inputs: Vec<ResourceAmount>,  // burned per unit crafted
+    pub outputs: Vec<ResourceAmount>, // minted per unit crafted
+    pub duration: u64,                // seconds until a job can be claimed, 0 crafts instantly
+    pub active: bool,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CraftingJob {
+    pub id: u32,
+    pub owner: Address,
+    pub recipe_id: u32,
+    pub quantity: u32,
+    pub ready_at: u64,
+    pub claimed: bool,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DataKey {
+    Material(u32),
+    MaterialCount,
+    MaterialBalance(u32, Address),
+    Recipe(u32),
+    RecipeCount,
+    Job(u32),
+    JobCount,
+}
+
+// counters live in instance storage, everything else in persistent storage
+fn load<V: TryFromVal<Env, Val>>(env: &Env, key: DataKey) -> Option<V> {
+    ttl::get_persistent(env, &StorageKey::Crafting(key))
+}
+
+fn save<V: IntoVal<Env, Val>>(env: &Env, key: DataKey, value: &V) {
+    ttl::set_persistent(env, &StorageKey::Crafting(key), value);
+}
+
+fn require_admin(env: &Env, admin: &Address) -> Result<(), CraftingError> {
+    access::require_role(env, admin, Role::Admin).map_err(|_| CraftingError::Unauthorized)
+}
+
+fn next_id(env: &Env, key: DataKey) -> u32 {
+    let id = ttl::get_instance::<u32>(env, &StorageKey::Crafting(key.clone())).unwrap_or(0) + 1;
+    ttl::set_instance(env, &StorageKey::Crafting(key), &id);
+    id
+}
+
+pub(crate) fn material_balance(env: &Env, material_id: u32, owner: &Address) -> i128 {
+    load(env, DataKey::MaterialBalance(material_id, owner.clone())).unwrap_or(0)
+}
+
+pub(crate) fn mint_material(env: &Env, material_id: u32, to: &Address, amount: i128) {
+    let balance = material_balance(env, material_id, to);
+    save(env, DataKey::MaterialBalance(material_id, to.clone()), &(balance + amount));
+    events::material_minted(env, material_id, to, amount);
+}
+
+pub(crate) fn burn_material(env: &Env, material_id: u32, from: &Address, amount: i128) -> Result<(), CraftingError> {
+    let balance = material_balance(env, material_id, from);
+    if balance < amount {
+        return Err(CraftingError::InsufficientResources);
+    }
+    save(env, DataKey::MaterialBalance(material_id, from.clone()), &(balance - amount));
+    events::material_burned(env, material_id, from, amount);
+    Ok(())
+}
+
+/// burn a metal or a material from `from`
+pub(crate) fn burn_resource(env: &Env, from: &Address, resource: &ResourceAmount, quantity: i128) -> Result<(), CraftingError> {
+    let amount = resource.amount * quantity;
+    match &resource.resource {
+        Resource::Metal(metal_type) => token::burn(env, metal_type, from, amount)
+            .map_err(|_| CraftingError::InsufficientResources),
+        Resource::Material(material_id) => burn_material(env, *material_id, from, amount),
+    }
+}
+
+fn mint_resource(env: &Env, to: &Address, resource: &ResourceAmount, quantity: i128) {
+    let amount = resource.amount * quantity;
+    match &resource.resource {
+        Resource::Metal(metal_type) => token::mint(env, metal_type, to, amount),
+        Resource::Material(material_id) => mint_material(env, *material_id, to, amount),
+    }
+}
+
+/// every amount has to be positive and every metal and material has to exist
+pub(crate) fn check_resources(env: &Env, resources: &Vec<ResourceAmount>) -> bool {
+    resources.iter().all(|resource| {
+        resource.amount > 0
+            && match resource.resource {
+                Resource::Metal(metal_type) => metals::load_metal(env, &metal_type).is_some(),
+                Resource::Material(material_id) => has_material(env, material_id),
+            }
+    })
+}
+
+fn has_material(env: &Env, material_id: u32) -> bool {
+    ttl::has_persistent(env, &StorageKey::Crafting(DataKey::Material(material_id)))
+}
+
+#[contractimpl]
+impl GameContract {
+    /// register a craftable material, admin only
+    pub fn add_material(env: Env, admin: Address, name: String, symbol: String) -> Result<u32, CraftingError> {
+        require_admin(&env, &admin)?;
+
+        let id = next_id(&env, DataKey::MaterialCount);
+        let material = Material { id, name, symbol };
+        save(&env, DataKey::Material(id), &material);
+        events::material_added(&env, &material);
+        Ok(id)
+    }
+
+    /// get material data
+    pub fn get_material(env: Env, material_id: u32) -> Option<Material> {
+        load(&env, DataKey::Material(material_id))
+    }
+
+    /// get material balance
+    pub fn material_balance(env: Env, material_id: u32, owner: Address) -> i128 {
+        material_balance(&env, material_id, &owner)
+    }
+
+    /// add a recipe, admin only
+    pub fn add_recipe(
+        env: Env,
+        admin: Address,
+        name: String,
+        inputs: Vec<ResourceAmount>,
+        outputs: Vec<ResourceAmount>,
+        duration: u64,
+    ) -> Result<u32, CraftingError> {
+        require_admin(&env, &admin)?;
+        if inputs.is_empty() || outputs.is_empty() {
+            return Err(CraftingError::InvalidRecipe);
+        }
+        if !check_resources(&env, &inputs) || !check_resources(&env, &outputs) {
+            return Err(CraftingError::InvalidRecipe);
+        }
+
+        let id = next_id(&env, DataKey::RecipeCount);
+        let recipe = Recipe { id, name, inputs, outputs, duration, active: true };
+        save(&env, DataKey::Recipe(id), &recipe);
+        events::recipe_updated(&env, &recipe);
+        Ok(id)
+    }
+
+    /// enable or disable a recipe, admin only
+    pub fn set_recipe_active(env: Env, admin: Address, recipe_id: u32, active: bool) -> Result<(), CraftingError> {
+        require_admin(&env, &admin)?;
+
+        let mut recipe: Recipe = load(&env, DataKey::Recipe(recipe_id))
+            .ok_or(CraftingError::RecipeNotFound)?;
+        recipe.active = active;
+        save(&env, DataKey::Recipe(recipe_id), &recipe);
+        events::recipe_updated(&env, &recipe);
+        Ok(())
+    }
+
+    /// get recipe data
+    pub fn get_recipe(env: Env, recipe_id: u32) -> Option<Recipe> {
+        load(&env, DataKey::Recipe(recipe_id))
+    }
+
+    /// get number of recipes, ids run from 1 to this
+    pub fn get_recipe_count(env: Env) -> u32 {
+        ttl::get_instance(&env, &StorageKey::Crafting(DataKey::RecipeCount)).unwrap_or(0)
+    }
+
+    /// burn the inputs of `quantity` units of a recipe. Instant recipes mint
+    /// the outputs right away and return None, timed ones return a job id to
+    /// claim once ready
+    pub fn craft(env: Env, player: Address, recipe_id: u32, quantity: u32) -> Result<Option<u32>, CraftingError> {
+        player.require_auth();
+        if quantity == 0 {
+            return Err(CraftingError::InvalidQuantity);
+        }
+
+        let recipe: Recipe = load(&env, DataKey::Recipe(recipe_id))
+            .ok_or(CraftingError::RecipeNotFound)?;
+        if !recipe.active {
+            return Err(CraftingError::RecipeInactive);
+        }
+
+        for input in recipe.inputs.iter() {
+            burn_resource(&env, &player, &input, quantity as i128)?;
+        }
+
+        if recipe.duration == 0 {
+            for output in recipe.outputs.iter() {
+                mint_resource(&env, &player, &output, quantity as i128);
+            }
+            events::crafted(&env, &player, recipe_id, quantity);
+            return Ok(None);
+        }
+
+        let job = CraftingJob {
+            id: next_id(&env, DataKey::JobCount),
+            owner: player.clone(),
+            recipe_id,
+            quantity,
+            ready_at: env.ledger().timestamp() + recipe.duration,
+            claimed: false,
+        };
+        save(&env, DataKey::Job(job.id), &job);
+        events::craft_started(&env, &job);
+        Ok(Some(job.id))
+    }
+
+    /// mint the outputs of a finished crafting job
+    pub fn claim_craft(env: Env, player: Address, job_id: u32) -> Result<(), CraftingError> {
+        player.require_auth();
+
+        let mut job: CraftingJob = load(&env, DataKey::Job(job_id))
+            .ok_or(CraftingError::JobNotFound)?;
+        if job.owner != player {
+            return Err(CraftingError::Unauthorized);
+        }
+        if job.claimed {
+            return Err(CraftingError::JobAlreadyClaimed);
+        }
+        if env.ledger().timestamp() < job.ready_at {
+            return Err(CraftingError::JobNotReady);
+        }
+
+        // recipes never change their outputs once added
+        let recipe: Recipe = load(&env, DataKey::Recipe(job.recipe_id))
+            .ok_or(CraftingError::RecipeNotFound)?;
+        for output in recipe.outputs.iter() {
+            mint_resource(&env, &player, &output, job.quantity as i128);
+        }
+
+        job.claimed = true;
+        save(&env, DataKey::Job(job_id), &job);
+        events::crafted(&env, &player, job.recipe_id, job.quantity);
+        Ok(())
+    }
+
+    /// get crafting job data
+    pub fn get_craft_job(env: Env, job_id: u32) -> Option<CraftingJob> {
+        load(&env, DataKey::Job(job_id))
+    }
+}
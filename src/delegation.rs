@@ -0,0 +1,98 @@
+use soroban_sdk::{contractimpl, contracttype, Address, Env, Vec};
+
+use crate::errors::DelegationError;
+use crate::events;
+use crate::ttl;
+use crate::{GameContract, GameContractArgs, GameContractClient, StorageKey};
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DelegationScope {
+    All,             // every mine the owner may harvest
+    Mines(Vec<u32>), // only these mine ids
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Delegation {
+    pub owner: Address,
+    pub delegate: Address, // may harvest on the owner's behalf
+    pub scope: DelegationScope,
+    pub expires_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DataKey {
+    Delegation(Address, Address),
+}
+
+// delegations live in persistent storage, keyed by owner and delegate
+fn key(owner: &Address, delegate: &Address) -> StorageKey {
+    StorageKey::Delegation(DataKey::Delegation(owner.clone(), delegate.clone()))
+}
+
+fn active_delegation(env: &Env, owner: &Address, delegate: &Address) -> Option<Delegation> {
+    ttl::get_persistent::<Delegation>(env, &key(owner, delegate))
+        .filter(|delegation| env.ledger().timestamp() < delegation.expires_at)
+}
+
+/// whether `owner` currently lets `delegate` harvest `mine_id`
+pub(crate) fn may_harvest(env: &Env, owner: &Address, delegate: &Address, mine_id: u32) -> bool {
+    match active_delegation(env, owner, delegate) {
+        Some(delegation) => match delegation.scope {
+            DelegationScope::All => true,
+            DelegationScope::Mines(mine_ids) => mine_ids.contains(mine_id),
+        },
+        None => false,
+    }
+}
+
+#[contractimpl]
+impl GameContract {
+    /// let another address harvest some or all of the owner's mines until
+    /// `expires_at`, the output still goes to the owner. Replaces an earlier
+    /// grant to the same delegate
+    pub fn grant_harvester(
+        env: Env,
+        owner: Address,
+        delegate: Address,
+        scope: DelegationScope,
+        expires_at: u64,
+    ) -> Result<(), DelegationError> {
+        owner.require_auth();
+        if delegate == owner {
+            return Err(DelegationError::InvalidDelegate);
+        }
+        if expires_at <= env.ledger().timestamp() {
+            return Err(DelegationError::InvalidExpiry);
+        }
+
+        let delegation = Delegation {
+            owner,
+            delegate,
+            scope,
+            expires_at,
+        };
+        ttl::set_persistent(&env, &key(&delegation.owner, &delegation.delegate), &delegation);
+        events::harvester_granted(&env, &delegation);
+        Ok(())
+    }
+
+    /// take a harvest permission back
+    pub fn revoke_harvester(env: Env, owner: Address, delegate: Address) -> Result<(), DelegationError> {
+        owner.require_auth();
+        if !ttl::has_persistent(&env, &key(&owner, &delegate)) {
+            return Err(DelegationError::DelegationNotFound);
+        }
+
+        env.storage().persistent().remove(&key(&owner, &delegate));
+        events::harvester_revoked(&env, &owner, &delegate);
+        Ok(())
+    }
+
+    /// get the running harvest permission from an owner to a delegate
+    pub fn get_delegation(env: Env, owner: Address, delegate: Address) -> Option<Delegation> {
+        active_delegation(&env, &owner, &delegate)
+    }
+}
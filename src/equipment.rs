@@ -0,0 +1,340 @@
+use soroban_sdk::{contractimpl, contracttype, token::TokenClient, Address, Env, String, Vec};
+
+use crate::access::{self, Role};
+use crate::crafting::{self, ResourceAmount};
+use crate::errors::EquipmentError;
+use crate::events;
+use crate::mining::{self, Mine};
+use crate::shares;
+use crate::ttl;
+use crate::{GameContract, GameContractArgs, GameContractClient, StorageKey};
+
+// a mine holds at most this many items
+pub const MAX_EQUIPMENT_SLOTS: u32 = 3;
+
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum EquipmentKind {
+    Drill,
+    Cart,
+    Pump,
+}
+
+// Bonuses are in basis points on top of the mine's own stats, 2500 turns
+// efficiency 60 into 75.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Blueprint {
+    pub id: u32, // assigned by add_blueprint
+    pub name: String,
+    pub kind: EquipmentKind,
+    pub efficiency_bonus_bps: u32,
+    pub capacity_bonus_bps: u32,
+    pub max_durability: u32,            // harvests before the item breaks
+    pub craft_cost: Vec<ResourceAmount>, // empty if it can not be crafted
+    pub price: i128,                    // in the shop token, 0 if not for sale
+}
+
+// most a single item may add to a stat, so three equipped items stay well
+// inside the stat math
+const MAX_BONUS_BPS: u32 = 10_000;
+
+impl Blueprint {
+    fn is_valid(&self, env: &Env) -> bool {
+        self.efficiency_bonus_bps <= MAX_BONUS_BPS
+            && self.capacity_bonus_bps <= MAX_BONUS_BPS
+            && self.max_durability > 0
+            && self.price >= 0
+            && crafting::check_resources(env, &self.craft_cost)
+    }
+}
+
+// A single non-fungible item
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Equipment {
+    pub id: u32,
+    pub blueprint_id: u32,
+    pub owner: Address,
+    pub kind: EquipmentKind,
+    pub efficiency_bonus_bps: u32,
+    pub capacity_bonus_bps: u32,
+    pub durability: u32,
+    pub equipped_on: Option<u32>, // mine id
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Shop {
+    pub token: Address,
+    pub treasury: Address,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EquipmentBonus {
+    pub efficiency_bps: u32,
+    pub capacity_bps: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DataKey {
+    Blueprint(u32),
+    BlueprintCount,
+    Equipment(u32),
+    EquipmentCount,
+    PlayerEquipment(Address),
+    Shop,
+}
+
+// counters and the shop live in instance storage, everything else in
+// persistent storage
+fn load_blueprint(env: &Env, blueprint_id: u32) -> Option<Blueprint> {
+    ttl::get_persistent(env, &StorageKey::Equipment(DataKey::Blueprint(blueprint_id)))
+}
+
+fn load_equipment(env: &Env, equipment_id: u32) -> Option<Equipment> {
+    ttl::get_persistent(env, &StorageKey::Equipment(DataKey::Equipment(equipment_id)))
+}
+
+fn save_equipment(env: &Env, item: &Equipment) {
+    ttl::set_persistent(env, &StorageKey::Equipment(DataKey::Equipment(item.id)), item);
+}
+
+fn load_player_equipment(env: &Env, player: &Address) -> Vec<u32> {
+    ttl::get_persistent(env, &StorageKey::Equipment(DataKey::PlayerEquipment(player.clone())))
+        .unwrap_or(Vec::new(env))
+}
+
+fn save_player_equipment(env: &Env, player: &Address, items: &Vec<u32>) {
+    ttl::set_persistent(
+        env,
+        &StorageKey::Equipment(DataKey::PlayerEquipment(player.clone())),
+        items,
+    );
+}
+
+fn require_admin(env: &Env, admin: &Address) -> Result<(), EquipmentError> {
+    access::require_role(env, admin, Role::Admin).map_err(|_| EquipmentError::Unauthorized)
+}
+
+fn remove_id(ids: &mut Vec<u32>, id: u32) {
+    if let Some(index) = ids.first_index_of(id) {
+        ids.remove(index);
+    }
+}
+
+// create a new item from a blueprint for `owner`
+fn issue(env: &Env, blueprint: &Blueprint, owner: &Address) -> u32 {
+    let id = ttl::get_instance::<u32>(env, &StorageKey::Equipment(DataKey::EquipmentCount))
+        .unwrap_or(0)
+        + 1;
+    ttl::set_instance(env, &StorageKey::Equipment(DataKey::EquipmentCount), &id);
+
+    let item = Equipment {
+        id,
+        blueprint_id: blueprint.id,
+        owner: owner.clone(),
+        kind: blueprint.kind,
+        efficiency_bonus_bps: blueprint.efficiency_bonus_bps,
+        capacity_bonus_bps: blueprint.capacity_bonus_bps,
+        durability: blueprint.max_durability,
+        equipped_on: None,
+    };
+    save_equipment(env, &item);
+
+    let mut items = load_player_equipment(env, owner);
+    items.push_back(id);
+    save_player_equipment(env, owner, &items);
+
+    events::equipment_issued(env, &item);
+    id
+}
+
+/// summed bonus of everything equipped on a mine
+pub(crate) fn bonus(env: &Env, mine: &Mine) -> EquipmentBonus {
+    let mut bonus = EquipmentBonus { efficiency_bps: 0, capacity_bps: 0 };
+    for equipment_id in mine.equipment.iter() {
+        if let Some(item) = load_equipment(env, equipment_id) {
+            bonus.efficiency_bps = bonus.efficiency_bps.saturating_add(item.efficiency_bonus_bps);
+            bonus.capacity_bps = bonus.capacity_bps.saturating_add(item.capacity_bonus_bps);
+        }
+    }
+    bonus
+}
+
+/// every equipped item loses one durability per harvest, broken items are
+/// taken off the mine. The caller saves the mine.
+pub(crate) fn wear(env: &Env, mine: &mut Mine) {
+    for equipment_id in mine.equipment.clone().iter() {
+        let Some(mut item) = load_equipment(env, equipment_id) else {
+            continue;
+        };
+        item.durability = item.durability.saturating_sub(1);
+        if item.durability == 0 {
+            item.equipped_on = None;
+            remove_id(&mut mine.equipment, equipment_id);
+            events::equipment_broken(env, &item, mine.id);
+        }
+        save_equipment(env, &item);
+    }
+}
+
+/// take everything off a mine, the items stay with their owner. The caller
+/// saves the mine.
+pub(crate) fn unequip_all(env: &Env, mine: &mut Mine) {
+    for equipment_id in mine.equipment.iter() {
+        if let Some(mut item) = load_equipment(env, equipment_id) {
+            item.equipped_on = None;
+            save_equipment(env, &item);
+            events::unequipped(env, &item, mine.id);
+        }
+    }
+    mine.equipment = Vec::new(env);
+}
+
+#[contractimpl]
+impl GameContract {
+    /// add an equipment blueprint, admin only
+    pub fn add_blueprint(env: Env, admin: Address, blueprint: Blueprint) -> Result<u32, EquipmentError> {
+        require_admin(&env, &admin)?;
+        if !blueprint.is_valid(&env) {
+            return Err(EquipmentError::InvalidBlueprint);
+        }
+
+        let id = ttl::get_instance::<u32>(&env, &StorageKey::Equipment(DataKey::BlueprintCount))
+            .unwrap_or(0)
+            + 1;
+        ttl::set_instance(&env, &StorageKey::Equipment(DataKey::BlueprintCount), &id);
+
+        let blueprint = Blueprint { id, ..blueprint };
+        ttl::set_persistent(&env, &StorageKey::Equipment(DataKey::Blueprint(id)), &blueprint);
+        events::blueprint_added(&env, &blueprint);
+        Ok(id)
+    }
+
+    /// get blueprint data
+    pub fn get_blueprint(env: Env, blueprint_id: u32) -> Option<Blueprint> {
+        load_blueprint(&env, blueprint_id)
+    }
+
+    /// set token and treasury used by buy_equipment, admin only
+    pub fn set_equipment_shop(env: Env, admin: Address, shop: Shop) -> Result<(), EquipmentError> {
+        require_admin(&env, &admin)?;
+
+        ttl::set_instance(&env, &StorageKey::Equipment(DataKey::Shop), &shop);
+        events::shop_set(&env, &admin, &shop);
+        Ok(())
+    }
+
+    /// get shop settings
+    pub fn get_equipment_shop(env: Env) -> Option<Shop> {
+        ttl::get_instance(&env, &StorageKey::Equipment(DataKey::Shop))
+    }
+
+    /// craft an item by burning the blueprint's craft cost
+    pub fn craft_equipment(env: Env, player: Address, blueprint_id: u32) -> Result<u32, EquipmentError> {
+        player.require_auth();
+
+        let blueprint = load_blueprint(&env, blueprint_id)
+            .ok_or(EquipmentError::BlueprintNotFound)?;
+        if blueprint.craft_cost.is_empty() {
+            return Err(EquipmentError::NotCraftable);
+        }
+
+        for cost in blueprint.craft_cost.iter() {
+            crafting::burn_resource(&env, &player, &cost, 1)
+                .map_err(|_| EquipmentError::InsufficientResources)?;
+        }
+        Ok(issue(&env, &blueprint, &player))
+    }
+
+    /// buy an item for the blueprint's price in the shop token
+    pub fn buy_equipment(env: Env, player: Address, blueprint_id: u32) -> Result<u32, EquipmentError> {
+        player.require_auth();
+
+        let blueprint = load_blueprint(&env, blueprint_id)
+            .ok_or(EquipmentError::BlueprintNotFound)?;
+        if blueprint.price == 0 {
+            return Err(EquipmentError::NotForSale);
+        }
+        let shop: Shop = ttl::get_instance(&env, &StorageKey::Equipment(DataKey::Shop))
+            .ok_or(EquipmentError::ShopNotConfigured)?;
+
+        let payment = TokenClient::new(&env, &shop.token);
+        if payment.balance(&player) < blueprint.price {
+            return Err(EquipmentError::InsufficientResources);
+        }
+        payment.transfer(&player, &shop.treasury, &blueprint.price);
+        Ok(issue(&env, &blueprint, &player))
+    }
+
+    /// get equipment data
+    pub fn get_equipment(env: Env, equipment_id: u32) -> Option<Equipment> {
+        load_equipment(&env, equipment_id)
+    }
+
+    /// get items owned by a player
+    pub fn get_player_equipment(env: Env, player: Address) -> Vec<u32> {
+        load_player_equipment(&env, &player)
+    }
+
+    /// put an item into a free slot of one of the owner's mines, shared mines
+    /// are held by the contract and take no equipment
+    pub fn equip(env: Env, mine_id: u32, equipment_id: u32) -> Result<(), EquipmentError> {
+        let mut mine = mining::load_mine(&env, mine_id)
+            .ok_or(EquipmentError::MineNotFound)?;
+        if shares::shared_mine(&env, mine_id).is_some() {
+            return Err(EquipmentError::MineShared);
+        }
+        mine.owner.require_auth();
+
+        let mut item = load_equipment(&env, equipment_id)
+            .ok_or(EquipmentError::EquipmentNotFound)?;
+        if item.owner != mine.owner {
+            return Err(EquipmentError::NotOwner);
+        }
+        if item.equipped_on.is_some() {
+            return Err(EquipmentError::AlreadyEquipped);
+        }
+        if item.durability == 0 {
+            return Err(EquipmentError::Broken);
+        }
+        if mine.equipment.len() >= MAX_EQUIPMENT_SLOTS {
+            return Err(EquipmentError::NoFreeSlot);
+        }
+
+        mining::accrue(&env, &mut mine);
+        item.equipped_on = Some(mine_id);
+        mine.equipment.push_back(equipment_id);
+        save_equipment(&env, &item);
+        mining::save_mine(&env, &mine);
+        events::equipped(&env, &item, mine_id);
+        Ok(())
+    }
+
+    /// take an item off a mine, it stays with the owner
+    pub fn unequip(env: Env, mine_id: u32, equipment_id: u32) -> Result<(), EquipmentError> {
+        let mut mine = mining::load_mine(&env, mine_id)
+            .ok_or(EquipmentError::MineNotFound)?;
+        if shares::shared_mine(&env, mine_id).is_some() {
+            return Err(EquipmentError::MineShared);
+        }
+        mine.owner.require_auth();
+
+        let mut item = load_equipment(&env, equipment_id)
+            .ok_or(EquipmentError::EquipmentNotFound)?;
+        if item.equipped_on != Some(mine_id) {
+            return Err(EquipmentError::NotEquipped);
+        }
+
+        mining::accrue(&env, &mut mine);
+        item.equipped_on = None;
+        remove_id(&mut mine.equipment, equipment_id);
+        save_equipment(&env, &item);
+        mining::save_mine(&env, &mine);
+        events::unequipped(&env, &item, mine_id);
+        Ok(())
+    }
+}
@@ -0,0 +1,280 @@
+use soroban_sdk::contracterror;
+
+// Error codes are part of the public interface, never renumber a variant.
+// Each module gets its own range: player 100, mining 200, access control 300,
+// metal tokens 400, crafting 500, equipment 600, marketplace 700, auctions 800,
+// leases 900, shares 1000, delegations 1100 and the metal registry 1200, so a
+// failure that bubbles up through a cross-contract call can still be told
+// apart.
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum PlayerError {
+    /// Address already has a player record
+    AlreadyRegistered = 100,
+    /// No player record for the address
+    PlayerNotFound = 101,
+    /// Caller is missing the role required for the call
+    Unauthorized = 102,
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum MiningError {
+    /// No mine with the given id
+    MineNotFound = 200,
+    /// Harvest cooldown has not passed, or nothing was produced since the last harvest
+    HarvestTooEarly = 201,
+    /// Mine is already at the maximum upgrade level
+    MaxUpgradeLevel = 202,
+    /// Player contract has not been linked yet
+    NotInitialized = 204,
+    /// Caller is missing the role required for the call
+    Unauthorized = 205,
+    /// Owner does not hold enough metal or fee token for the upgrade
+    CannotAffordUpgrade = 206,
+    /// Upgrade fee can not be negative
+    InvalidUpgradeFee = 207,
+    /// Mine has no ore left
+    MineExhausted = 208,
+    /// Mine can not be transferred to its current owner
+    InvalidTransfer = 209,
+    /// Mine is held in escrow, leased or shared
+    MineLocked = 210,
+    /// Caller has no harvest permission for the mine
+    NotDelegated = 211,
+    /// Metal is not in the registry
+    UnknownMetal = 212,
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum AccessError {
+    /// Caller is missing the role required for the call
+    Unauthorized = 300,
+    /// Admin role can only be moved with transfer_admin
+    InvalidRole = 301,
+    /// Thresholds must be below their extend_to and within the network max ttl
+    InvalidTtlConfig = 302,
+    /// A game parameter is out of its bounds
+    InvalidGameConfig = 303,
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum TokenError {
+    /// Balance is lower than the amount
+    InsufficientBalance = 400,
+    /// Allowance is lower than the amount or has expired
+    InsufficientAllowance = 401,
+    /// Amounts can not be negative
+    NegativeAmount = 402,
+    /// Expiration ledger is in the past
+    InvalidExpiration = 403,
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum CraftingError {
+    /// Caller is missing the role required for the call, or does not own the job
+    Unauthorized = 500,
+    /// No recipe with the given id
+    RecipeNotFound = 501,
+    /// Recipe has been disabled
+    RecipeInactive = 502,
+    /// Recipe needs inputs and outputs with positive amounts of known resources
+    InvalidRecipe = 503,
+    /// Quantity must be at least 1
+    InvalidQuantity = 504,
+    /// Player does not hold enough of an input
+    InsufficientResources = 505,
+    /// No crafting job with the given id
+    JobNotFound = 506,
+    /// Crafting job is still running
+    JobNotReady = 507,
+    /// Crafting job was already claimed
+    JobAlreadyClaimed = 508,
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum EquipmentError {
+    /// Caller is missing the role required for the call
+    Unauthorized = 600,
+    /// No blueprint with the given id
+    BlueprintNotFound = 601,
+    /// Blueprint needs durability, bonuses of at most 10000 bps, a non-negative price and a valid craft cost
+    InvalidBlueprint = 602,
+    /// Blueprint has no craft cost
+    NotCraftable = 603,
+    /// Blueprint has no price
+    NotForSale = 604,
+    /// Shop token and treasury have not been set
+    ShopNotConfigured = 605,
+    /// Player can not pay the craft cost or price
+    InsufficientResources = 606,
+    /// No item with the given id
+    EquipmentNotFound = 607,
+    /// No mine with the given id
+    MineNotFound = 608,
+    /// Item does not belong to the mine owner
+    NotOwner = 609,
+    /// Item is already on a mine
+    AlreadyEquipped = 610,
+    /// Item is not on this mine
+    NotEquipped = 611,
+    /// Item has no durability left
+    Broken = 612,
+    /// Every slot of the mine is taken
+    NoFreeSlot = 613,
+    /// Shared mines can not be equipped
+    MineShared = 614,
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum MarketError {
+    /// Caller is missing the role required for the call
+    Unauthorized = 700,
+    /// Payment token and treasury have not been set
+    NotConfigured = 701,
+    /// Fee is above 100%
+    InvalidConfig = 702,
+    /// Price must be positive
+    InvalidPrice = 703,
+    /// Amount must be positive
+    InvalidAmount = 704,
+    /// No open listing with the given id
+    ListingNotFound = 705,
+    /// No mine with the given id
+    MineNotFound = 706,
+    /// Mine is already held in escrow
+    MineLocked = 707,
+    /// Seller does not hold enough metal
+    InsufficientBalance = 708,
+    /// Buyer does not hold enough of the payment token
+    InsufficientFunds = 709,
+    /// Sellers can not buy their own listing
+    SelfPurchase = 710,
+    /// Escrowed item could not be handed over
+    TransferFailed = 711,
+    /// Buyer of a mine has no player record
+    NotRegistered = 712,
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum AuctionError {
+    /// Payment token and treasury have not been set on the marketplace
+    NotConfigured = 800,
+    /// No mine with the given id
+    MineNotFound = 801,
+    /// Mine is already held in escrow
+    MineLocked = 802,
+    /// Reserve must be positive and below the dutch start price
+    InvalidPrice = 803,
+    /// End time must be in the future
+    InvalidEndTime = 804,
+    /// No open auction with the given id
+    AuctionNotFound = 805,
+    /// Auction is past its end time
+    AuctionEnded = 806,
+    /// Auction is still running
+    AuctionNotEnded = 807,
+    /// Bid is below the minimum bid
+    BidTooLow = 808,
+    /// Sellers can not bid on their own auction
+    SelfBid = 809,
+    /// Bidder does not hold enough of the payment token
+    InsufficientFunds = 810,
+    /// Auction with bids can not be cancelled
+    HasBids = 811,
+    /// Bidder has no player record
+    NotRegistered = 812,
+    /// Mine could not be handed over
+    TransferFailed = 813,
+    /// No outbid amount waiting to be claimed
+    NothingToClaim = 814,
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum LeaseError {
+    /// No mine with the given id
+    MineNotFound = 900,
+    /// Mine is held in escrow or already leased
+    MineLocked = 901,
+    /// Duration is zero or too long
+    InvalidDuration = 902,
+    /// Revenue share is above 100%
+    InvalidShare = 903,
+    /// Owners can not lease a mine to themselves
+    InvalidLessee = 904,
+    /// Lessee has no player record
+    NotRegistered = 905,
+    /// Player contract has not been linked yet
+    NotInitialized = 906,
+    /// Mine has no running lease
+    LeaseNotFound = 907,
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum SharesError {
+    /// No mine with the given id
+    MineNotFound = 1000,
+    /// Mine is held in escrow, leased or already split
+    MineLocked = 1001,
+    /// A mine has to be split into at least 2 shares
+    InvalidShares = 1002,
+    /// Mine has not been split into shares
+    NotShared = 1003,
+    /// Address does not hold enough shares
+    InsufficientShares = 1004,
+    /// Mine already has the maximum number of shareholders
+    TooManyHolders = 1005,
+    /// Share amount must be positive and go to another address
+    InvalidAmount = 1006,
+    /// No harvested metal waiting to be claimed
+    NothingToClaim = 1007,
+    /// Metal or mine could not be handed over
+    TransferFailed = 1008,
+    /// Redeeming address has no player record
+    NotRegistered = 1009,
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum DelegationError {
+    /// Owners can not delegate to themselves
+    InvalidDelegate = 1100,
+    /// Expiry must be in the future
+    InvalidExpiry = 1101,
+    /// No harvest permission from the owner to the delegate
+    DelegationNotFound = 1102,
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum MetalError {
+    /// Caller is missing the role required for the call
+    Unauthorized = 1200,
+    /// Metal is already registered
+    MetalExists = 1201,
+    /// Metal is not in the registry
+    MetalNotFound = 1202,
+    /// Efficiency must be 1-100, stats positive and within their bounds and name and symbol set
+    InvalidMetal = 1203,
+}
@@ -0,0 +1,439 @@
+use soroban_sdk::{symbol_short, Address, Env, String, Symbol};
+
+use crate::access::Role;
+use crate::config::GameConfig;
+use crate::auction::Auction;
+use crate::crafting::{CraftingJob, Material, Recipe};
+use crate::delegation::Delegation;
+use crate::equipment::{Blueprint, Equipment, Shop};
+use crate::lease::Lease;
+use crate::marketplace::{Listing, MarketConfig};
+use crate::metals::Metal;
+use crate::mining::{MetalType, Mine, MinedResource, UpgradeFee};
+use crate::shares::SharedMine;
+use crate::ttl::TtlConfig;
+
+// Every event is published with topics `(module, action, subject)` where the
+// subject is the address or mine id the event is about. The data layouts are
+// listed next to each function and in the README, indexers rely on them, so
+// only ever append fields at the end.
+
+const PLAYER: Symbol = symbol_short!("player");
+const MINE: Symbol = symbol_short!("mine");
+const ACCESS: Symbol = symbol_short!("access");
+const TOKEN: Symbol = symbol_short!("token");
+const CRAFT: Symbol = symbol_short!("craft");
+const EQUIP: Symbol = symbol_short!("equip");
+const MARKET: Symbol = symbol_short!("market");
+const AUCTION: Symbol = symbol_short!("auction");
+const LEASE: Symbol = symbol_short!("lease");
+const SHARES: Symbol = symbol_short!("shares");
+const DELEGATE: Symbol = symbol_short!("delegate");
+const METAL: Symbol = symbol_short!("metal");
+
+/// ("player", "register", player) -> username
+pub(crate) fn player_registered(env: &Env, player: &Address, username: &String) {
+    env.events()
+        .publish((PLAYER, symbol_short!("register"), player.clone()), username.clone());
+}
+
+/// ("player", "rename", player) -> username
+pub(crate) fn username_changed(env: &Env, player: &Address, username: &String) {
+    env.events()
+        .publish((PLAYER, symbol_short!("rename"), player.clone()), username.clone());
+}
+
+/// ("player", "exp", player) -> (exp_gained, experience)
+pub(crate) fn experience_gained(env: &Env, player: &Address, exp_gained: u64, experience: u64) {
+    env.events()
+        .publish((PLAYER, symbol_short!("exp"), player.clone()), (exp_gained, experience));
+}
+
+/// ("player", "level_up", player) -> (old_level, new_level)
+pub(crate) fn level_up(env: &Env, player: &Address, old_level: u32, new_level: u32) {
+    env.events()
+        .publish((PLAYER, symbol_short!("level_up"), player.clone()), (old_level, new_level));
+}
+
+/// ("player", "mined", player) -> (amount, total_mined)
+pub(crate) fn total_mined_updated(env: &Env, player: &Address, amount: u64, total_mined: u64) {
+    env.events()
+        .publish((PLAYER, symbol_short!("mined"), player.clone()), (amount, total_mined));
+}
+
+/// ("player", "mine_add", player) -> mine_id
+pub(crate) fn active_mine_added(env: &Env, player: &Address, mine_id: u32) {
+    env.events()
+        .publish((PLAYER, symbol_short!("mine_add"), player.clone()), mine_id);
+}
+
+/// ("player", "mine_rm", player) -> mine_id
+pub(crate) fn active_mine_removed(env: &Env, player: &Address, mine_id: u32) {
+    env.events()
+        .publish((PLAYER, symbol_short!("mine_rm"), player.clone()), mine_id);
+}
+
+/// ("mine", "created", mine_id) -> (owner, metal_type, efficiency, capacity, reserve, seed)
+pub(crate) fn mine_created(env: &Env, mine: &Mine) {
+    env.events().publish(
+        (MINE, symbol_short!("created"), mine.id),
+        (
+            mine.owner.clone(),
+            mine.metal_type.clone(),
+            mine.efficiency,
+            mine.capacity,
+            mine.reserve,
+            mine.seed,
+        ),
+    );
+}
+
+/// ("mine", "harvested", mine_id) -> (owner, MinedResource)
+pub(crate) fn mine_harvested(env: &Env, mine: &Mine, resource: &MinedResource) {
+    env.events().publish(
+        (MINE, symbol_short!("harvested"), mine.id),
+        (mine.owner.clone(), resource.clone()),
+    );
+}
+
+/// ("mine", "exhausted", mine_id) -> owner
+pub(crate) fn mine_exhausted(env: &Env, mine: &Mine) {
+    env.events()
+        .publish((MINE, symbol_short!("exhausted"), mine.id), mine.owner.clone());
+}
+
+/// ("mine", "transfer", mine_id) -> (from, to)
+pub(crate) fn mine_transferred(env: &Env, mine_id: u32, from: &Address, to: &Address) {
+    env.events()
+        .publish((MINE, symbol_short!("transfer"), mine_id), (from.clone(), to.clone()));
+}
+
+/// ("mine", "upgraded", mine_id) -> (upgrade_level, efficiency, capacity)
+pub(crate) fn mine_upgraded(env: &Env, mine: &Mine) {
+    env.events().publish(
+        (MINE, symbol_short!("upgraded"), mine.id),
+        (mine.upgrade_level, mine.efficiency, mine.capacity),
+    );
+}
+
+/// ("mine", "link", player_contract) -> ()
+pub(crate) fn player_contract_set(env: &Env, player_contract: &Address) {
+    env.events()
+        .publish((MINE, symbol_short!("link"), player_contract.clone()), ());
+}
+
+/// ("mine", "fee", admin) -> Option<UpgradeFee>
+pub(crate) fn upgrade_fee_set(env: &Env, admin: &Address, fee: &Option<UpgradeFee>) {
+    env.events()
+        .publish((MINE, symbol_short!("fee"), admin.clone()), fee.clone());
+}
+
+/// ("access", "grant", account) -> role
+pub(crate) fn role_granted(env: &Env, account: &Address, role: Role) {
+    env.events()
+        .publish((ACCESS, symbol_short!("grant"), account.clone()), role);
+}
+
+/// ("access", "revoke", account) -> role
+pub(crate) fn role_revoked(env: &Env, account: &Address, role: Role) {
+    env.events()
+        .publish((ACCESS, symbol_short!("revoke"), account.clone()), role);
+}
+
+/// ("access", "admin", new_admin) -> old_admin
+pub(crate) fn admin_transferred(env: &Env, old_admin: &Address, new_admin: &Address) {
+    env.events()
+        .publish((ACCESS, symbol_short!("admin"), new_admin.clone()), old_admin.clone());
+}
+
+/// ("access", "ttl", admin) -> TtlConfig
+pub(crate) fn ttl_config_set(env: &Env, admin: &Address, config: &TtlConfig) {
+    env.events()
+        .publish((ACCESS, symbol_short!("ttl"), admin.clone()), config.clone());
+}
+
+/// ("access", "game", admin) -> GameConfig
+pub(crate) fn game_config_set(env: &Env, admin: &Address, config: &GameConfig) {
+    env.events()
+        .publish((ACCESS, symbol_short!("game"), admin.clone()), config.clone());
+}
+
+/// ("token", "mint", to) -> (metal_type, amount)
+pub(crate) fn metal_minted(env: &Env, metal_type: &MetalType, to: &Address, amount: i128) {
+    env.events()
+        .publish((TOKEN, symbol_short!("mint"), to.clone()), (metal_type.clone(), amount));
+}
+
+/// ("token", "burn", from) -> (metal_type, amount)
+pub(crate) fn metal_burned(env: &Env, metal_type: &MetalType, from: &Address, amount: i128) {
+    env.events()
+        .publish((TOKEN, symbol_short!("burn"), from.clone()), (metal_type.clone(), amount));
+}
+
+/// ("token", "transfer", from) -> (to, metal_type, amount)
+pub(crate) fn metal_transferred(env: &Env, metal_type: &MetalType, from: &Address, to: &Address, amount: i128) {
+    env.events().publish(
+        (TOKEN, symbol_short!("transfer"), from.clone()),
+        (to.clone(), metal_type.clone(), amount),
+    );
+}
+
+/// ("token", "approve", from) -> (spender, metal_type, amount, expiration_ledger)
+pub(crate) fn metal_approved(
+    env: &Env,
+    metal_type: &MetalType,
+    from: &Address,
+    spender: &Address,
+    amount: i128,
+    expiration_ledger: u32,
+) {
+    env.events().publish(
+        (TOKEN, symbol_short!("approve"), from.clone()),
+        (spender.clone(), metal_type.clone(), amount, expiration_ledger),
+    );
+}
+
+/// ("craft", "material", material_id) -> (name, symbol)
+pub(crate) fn material_added(env: &Env, material: &Material) {
+    env.events().publish(
+        (CRAFT, symbol_short!("material"), material.id),
+        (material.name.clone(), material.symbol.clone()),
+    );
+}
+
+/// ("craft", "recipe", recipe_id) -> Recipe
+pub(crate) fn recipe_updated(env: &Env, recipe: &Recipe) {
+    env.events()
+        .publish((CRAFT, symbol_short!("recipe"), recipe.id), recipe.clone());
+}
+
+/// ("craft", "mint", to) -> (material_id, amount)
+pub(crate) fn material_minted(env: &Env, material_id: u32, to: &Address, amount: i128) {
+    env.events()
+        .publish((CRAFT, symbol_short!("mint"), to.clone()), (material_id, amount));
+}
+
+/// ("craft", "burn", from) -> (material_id, amount)
+pub(crate) fn material_burned(env: &Env, material_id: u32, from: &Address, amount: i128) {
+    env.events()
+        .publish((CRAFT, symbol_short!("burn"), from.clone()), (material_id, amount));
+}
+
+/// ("craft", "started", owner) -> (job_id, recipe_id, quantity, ready_at)
+pub(crate) fn craft_started(env: &Env, job: &CraftingJob) {
+    env.events().publish(
+        (CRAFT, symbol_short!("started"), job.owner.clone()),
+        (job.id, job.recipe_id, job.quantity, job.ready_at),
+    );
+}
+
+/// ("craft", "crafted", player) -> (recipe_id, quantity)
+pub(crate) fn crafted(env: &Env, player: &Address, recipe_id: u32, quantity: u32) {
+    env.events()
+        .publish((CRAFT, symbol_short!("crafted"), player.clone()), (recipe_id, quantity));
+}
+
+/// ("equip", "blueprint", blueprint_id) -> Blueprint
+pub(crate) fn blueprint_added(env: &Env, blueprint: &Blueprint) {
+    env.events()
+        .publish((EQUIP, symbol_short!("blueprint"), blueprint.id), blueprint.clone());
+}
+
+/// ("equip", "shop", admin) -> Shop
+pub(crate) fn shop_set(env: &Env, admin: &Address, shop: &Shop) {
+    env.events()
+        .publish((EQUIP, symbol_short!("shop"), admin.clone()), shop.clone());
+}
+
+/// ("equip", "issued", equipment_id) -> (owner, blueprint_id)
+pub(crate) fn equipment_issued(env: &Env, item: &Equipment) {
+    env.events().publish(
+        (EQUIP, symbol_short!("issued"), item.id),
+        (item.owner.clone(), item.blueprint_id),
+    );
+}
+
+/// ("equip", "equipped", equipment_id) -> mine_id
+pub(crate) fn equipped(env: &Env, item: &Equipment, mine_id: u32) {
+    env.events()
+        .publish((EQUIP, symbol_short!("equipped"), item.id), mine_id);
+}
+
+/// ("equip", "removed", equipment_id) -> mine_id
+pub(crate) fn unequipped(env: &Env, item: &Equipment, mine_id: u32) {
+    env.events()
+        .publish((EQUIP, symbol_short!("removed"), item.id), mine_id);
+}
+
+/// ("equip", "broken", equipment_id) -> mine_id
+pub(crate) fn equipment_broken(env: &Env, item: &Equipment, mine_id: u32) {
+    env.events()
+        .publish((EQUIP, symbol_short!("broken"), item.id), mine_id);
+}
+
+/// ("market", "config", admin) -> MarketConfig
+pub(crate) fn market_config_set(env: &Env, admin: &Address, config: &MarketConfig) {
+    env.events()
+        .publish((MARKET, symbol_short!("config"), admin.clone()), config.clone());
+}
+
+/// ("market", "listed", listing_id) -> (seller, ListingItem, price)
+pub(crate) fn listing_created(env: &Env, listing: &Listing) {
+    env.events().publish(
+        (MARKET, symbol_short!("listed"), listing.id),
+        (listing.seller.clone(), listing.item.clone(), listing.price),
+    );
+}
+
+/// ("market", "cancelled", listing_id) -> seller
+pub(crate) fn listing_cancelled(env: &Env, listing: &Listing) {
+    env.events()
+        .publish((MARKET, symbol_short!("cancelled"), listing.id), listing.seller.clone());
+}
+
+/// ("market", "sold", listing_id) -> (seller, buyer, price)
+pub(crate) fn listing_sold(env: &Env, listing: &Listing, buyer: &Address) {
+    env.events().publish(
+        (MARKET, symbol_short!("sold"), listing.id),
+        (listing.seller.clone(), buyer.clone(), listing.price),
+    );
+}
+
+/// ("auction", "started", auction_id) -> (seller, mine_id, AuctionKind, start_price, reserve_price, end_time)
+pub(crate) fn auction_started(env: &Env, auction: &Auction) {
+    env.events().publish(
+        (AUCTION, symbol_short!("started"), auction.id),
+        (
+            auction.seller.clone(),
+            auction.mine_id,
+            auction.kind,
+            auction.start_price,
+            auction.reserve_price,
+            auction.end_time,
+        ),
+    );
+}
+
+/// ("auction", "bid", auction_id) -> (bidder, amount, end_time)
+pub(crate) fn bid_placed(env: &Env, auction: &Auction, bidder: &Address, amount: i128) {
+    env.events().publish(
+        (AUCTION, symbol_short!("bid"), auction.id),
+        (bidder.clone(), amount, auction.end_time),
+    );
+}
+
+/// ("auction", "refund", auction_id) -> (bidder, amount), the amount becomes claimable
+pub(crate) fn bid_refunded(env: &Env, auction_id: u32, bidder: &Address, amount: i128) {
+    env.events()
+        .publish((AUCTION, symbol_short!("refund"), auction_id), (bidder.clone(), amount));
+}
+
+/// ("auction", "claimed", bidder) -> (token, amount)
+pub(crate) fn refund_claimed(env: &Env, bidder: &Address, token: &Address, amount: i128) {
+    env.events()
+        .publish((AUCTION, symbol_short!("claimed"), bidder.clone()), (token.clone(), amount));
+}
+
+/// ("auction", "settled", auction_id) -> (seller, Option<winner>, price)
+pub(crate) fn auction_settled(env: &Env, auction: &Auction) {
+    env.events().publish(
+        (AUCTION, symbol_short!("settled"), auction.id),
+        (auction.seller.clone(), auction.highest_bidder.clone(), auction.highest_bid),
+    );
+}
+
+/// ("auction", "cancelled", auction_id) -> seller
+pub(crate) fn auction_cancelled(env: &Env, auction: &Auction) {
+    env.events()
+        .publish((AUCTION, symbol_short!("cancelled"), auction.id), auction.seller.clone());
+}
+
+/// ("lease", "started", mine_id) -> (owner, lessee, revenue_share_bps, end_time)
+pub(crate) fn mine_leased(env: &Env, lease: &Lease) {
+    env.events().publish(
+        (LEASE, symbol_short!("started"), lease.mine_id),
+        (lease.owner.clone(), lease.lessee.clone(), lease.revenue_share_bps, lease.end_time),
+    );
+}
+
+/// ("lease", "ended", mine_id) -> lessee
+pub(crate) fn lease_ended(env: &Env, lease: &Lease) {
+    env.events()
+        .publish((LEASE, symbol_short!("ended"), lease.mine_id), lease.lessee.clone());
+}
+
+/// ("lease", "harvest", mine_id) -> (lessee, owner_amount, lessee_amount)
+pub(crate) fn lease_harvested(env: &Env, lease: &Lease, owner_amount: u64, lessee_amount: u64) {
+    env.events().publish(
+        (LEASE, symbol_short!("harvest"), lease.mine_id),
+        (lease.lessee.clone(), owner_amount, lessee_amount),
+    );
+}
+
+/// ("shares", "split", mine_id) -> (owner, total_shares)
+pub(crate) fn mine_fractionalized(env: &Env, shared: &SharedMine) {
+    env.events().publish(
+        (SHARES, symbol_short!("split"), shared.mine_id),
+        (shared.upgrader.clone(), shared.total_shares),
+    );
+}
+
+/// ("shares", "transfer", mine_id) -> (from, to, amount)
+pub(crate) fn shares_transferred(env: &Env, mine_id: u32, from: &Address, to: &Address, amount: u32) {
+    env.events().publish(
+        (SHARES, symbol_short!("transfer"), mine_id),
+        (from.clone(), to.clone(), amount),
+    );
+}
+
+/// ("shares", "vote", mine_id) -> (holder, candidate)
+pub(crate) fn upgrader_voted(env: &Env, mine_id: u32, holder: &Address, candidate: &Address) {
+    env.events()
+        .publish((SHARES, symbol_short!("vote"), mine_id), (holder.clone(), candidate.clone()));
+}
+
+/// ("shares", "upgrader", mine_id) -> (upgrader, supporting_shares)
+pub(crate) fn upgrader_elected(env: &Env, mine_id: u32, upgrader: &Address, support: u32) {
+    env.events()
+        .publish((SHARES, symbol_short!("upgrader"), mine_id), (upgrader.clone(), support));
+}
+
+/// ("shares", "claim", holder) -> (MetalType, amount)
+pub(crate) fn output_claimed(env: &Env, holder: &Address, metal_type: &MetalType, amount: i128) {
+    env.events().publish(
+        (SHARES, symbol_short!("claim"), holder.clone()),
+        (metal_type.clone(), amount),
+    );
+}
+
+/// ("shares", "redeem", mine_id) -> holder
+pub(crate) fn mine_redeemed(env: &Env, mine_id: u32, holder: &Address) {
+    env.events()
+        .publish((SHARES, symbol_short!("redeem"), mine_id), holder.clone());
+}
+
+/// ("delegate", "granted", owner) -> (delegate, DelegationScope, expires_at)
+pub(crate) fn harvester_granted(env: &Env, delegation: &Delegation) {
+    env.events().publish(
+        (DELEGATE, symbol_short!("granted"), delegation.owner.clone()),
+        (delegation.delegate.clone(), delegation.scope.clone(), delegation.expires_at),
+    );
+}
+
+/// ("delegate", "revoked", owner) -> delegate
+pub(crate) fn harvester_revoked(env: &Env, owner: &Address, delegate: &Address) {
+    env.events()
+        .publish((DELEGATE, symbol_short!("revoked"), owner.clone()), delegate.clone());
+}
+
+/// ("metal", "added", MetalType) -> Metal
+pub(crate) fn metal_added(env: &Env, metal: &Metal) {
+    env.events()
+        .publish((METAL, symbol_short!("added"), metal.id.clone()), metal.clone());
+}
+
+/// ("metal", "updated", MetalType) -> Metal
+pub(crate) fn metal_updated(env: &Env, metal: &Metal) {
+    env.events()
+        .publish((METAL, symbol_short!("updated"), metal.id.clone()), metal.clone());
+}
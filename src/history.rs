@@ -0,0 +1,101 @@
+use soroban_sdk::{contractimpl, contracttype, Address, Env, Vec};
+
+use crate::mining::MinedResource;
+use crate::ttl;
+use crate::{GameContract, GameContractArgs, GameContractClient, StorageKey};
+
+// harvests kept per mine and per player, older ones are overwritten
+pub const MINE_HISTORY_SIZE: u32 = 50;
+pub const PLAYER_HISTORY_SIZE: u32 = 100;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DataKey {
+    MineEntry(u32, u32), // mine id, slot
+    MineCount(u32),      // harvests ever recorded for the mine
+    PlayerEntry(Address, u32),
+    PlayerCount(Address),
+}
+
+// ring buffers in persistent storage, entry `n` lives in slot `n % size` so a
+// harvest writes one entry and a counter instead of the whole buffer
+fn push(env: &Env, count_key: DataKey, entry_key: impl Fn(u32) -> DataKey, size: u32, record: &MinedResource) {
+    let count: u32 = ttl::get_persistent(env, &StorageKey::History(count_key.clone())).unwrap_or(0);
+    ttl::set_persistent(env, &StorageKey::History(entry_key(count % size)), record);
+    ttl::set_persistent(env, &StorageKey::History(count_key), &(count + 1));
+}
+
+// newest first, `offset` skips the newest entries
+fn page(
+    env: &Env,
+    count_key: DataKey,
+    entry_key: impl Fn(u32) -> DataKey,
+    size: u32,
+    offset: u32,
+    limit: u32,
+) -> Vec<MinedResource> {
+    let count: u32 = ttl::get_persistent(env, &StorageKey::History(count_key)).unwrap_or(0);
+    let kept = count.min(size);
+    let start = offset.min(kept);
+    let end = start.saturating_add(limit).min(kept);
+
+    let mut records = Vec::new(env);
+    for index in start..end {
+        let slot = (count - 1 - index) % size;
+        if let Some(record) = ttl::get_persistent(env, &StorageKey::History(entry_key(slot))) {
+            records.push_back(record);
+        }
+    }
+    records
+}
+
+/// remember a harvest in the mine's history
+pub(crate) fn record_mine(env: &Env, resource: &MinedResource) {
+    let mine_id = resource.mine_id;
+    push(
+        env,
+        DataKey::MineCount(mine_id),
+        |slot| DataKey::MineEntry(mine_id, slot),
+        MINE_HISTORY_SIZE,
+        resource,
+    );
+}
+
+/// remember the part of a harvest credited to a player, `resource.amount` is
+/// that part
+pub(crate) fn record_player(env: &Env, player: &Address, resource: &MinedResource) {
+    push(
+        env,
+        DataKey::PlayerCount(player.clone()),
+        |slot| DataKey::PlayerEntry(player.clone(), slot),
+        PLAYER_HISTORY_SIZE,
+        resource,
+    );
+}
+
+#[contractimpl]
+impl GameContract {
+    /// get a page of a mine's last harvests, newest first
+    pub fn get_mine_history(env: Env, mine_id: u32, offset: u32, limit: u32) -> Vec<MinedResource> {
+        page(
+            &env,
+            DataKey::MineCount(mine_id),
+            |slot| DataKey::MineEntry(mine_id, slot),
+            MINE_HISTORY_SIZE,
+            offset,
+            limit,
+        )
+    }
+
+    /// get a page of a player's last harvests, newest first
+    pub fn get_player_history(env: Env, player: Address, offset: u32, limit: u32) -> Vec<MinedResource> {
+        page(
+            &env,
+            DataKey::PlayerCount(player.clone()),
+            |slot| DataKey::PlayerEntry(player.clone(), slot),
+            PLAYER_HISTORY_SIZE,
+            offset,
+            limit,
+        )
+    }
+}
@@ -0,0 +1,93 @@
+use soroban_sdk::{contractimpl, contracttype, Address, Env, Vec};
+
+use crate::player::DataKey;
+use crate::ttl;
+use crate::{GameContract, GameContractArgs, GameContractClient, StorageKey};
+
+// only the top entries of each board are kept
+pub const LEADERBOARD_SIZE: u32 = 100;
+
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum LeaderboardKind {
+    TotalMined,
+    Experience,
+    Level,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LeaderboardEntry {
+    pub player: Address,
+    pub score: u64,
+}
+
+// 1-based rank on each board, None if the player is not in the top entries
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PlayerRank {
+    pub total_mined: Option<u32>,
+    pub experience: Option<u32>,
+    pub level: Option<u32>,
+}
+
+fn load_board(env: &Env, kind: LeaderboardKind) -> Vec<LeaderboardEntry> {
+    ttl::get_persistent(env, &StorageKey::Player(DataKey::Leaderboard(kind)))
+        .unwrap_or(Vec::new(env))
+}
+
+fn save_board(env: &Env, kind: LeaderboardKind, board: &Vec<LeaderboardEntry>) {
+    ttl::set_persistent(env, &StorageKey::Player(DataKey::Leaderboard(kind)), board);
+}
+
+fn position(board: &Vec<LeaderboardEntry>, player: &Address) -> Option<u32> {
+    board.iter().position(|entry| entry.player == *player).map(|i| i as u32)
+}
+
+/// move a player to the right place for their new score, boards are sorted
+/// high to low and earlier entries win ties
+pub(crate) fn record(env: &Env, kind: LeaderboardKind, player: &Address, score: u64) {
+    let mut board = load_board(env, kind);
+    if let Some(index) = position(&board, player) {
+        if board.get_unchecked(index).score == score {
+            return;
+        }
+        board.remove(index);
+    }
+
+    let index = board
+        .iter()
+        .position(|entry| entry.score < score)
+        .map(|i| i as u32)
+        .unwrap_or(board.len());
+    if index >= LEADERBOARD_SIZE {
+        return;
+    }
+
+    board.insert(index, LeaderboardEntry { player: player.clone(), score });
+    if board.len() > LEADERBOARD_SIZE {
+        board.pop_back();
+    }
+    save_board(env, kind, &board);
+}
+
+#[contractimpl]
+impl GameContract {
+    /// get a page of a leaderboard, best first
+    pub fn get_leaderboard(env: Env, kind: LeaderboardKind, offset: u32, limit: u32) -> Vec<LeaderboardEntry> {
+        let board = load_board(&env, kind);
+        let start = offset.min(board.len());
+        let end = start.saturating_add(limit.min(LEADERBOARD_SIZE)).min(board.len());
+        board.slice(start..end)
+    }
+
+    /// get a player's rank on every leaderboard
+    pub fn get_rank(env: Env, player: Address) -> PlayerRank {
+        let rank = |kind| position(&load_board(&env, kind), &player).map(|i| i + 1);
+        PlayerRank {
+            total_mined: rank(LeaderboardKind::TotalMined),
+            experience: rank(LeaderboardKind::Experience),
+            level: rank(LeaderboardKind::Level),
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use soroban_sdk::{contractimpl, contracttype, Address, Env};
+
+use crate::errors::LeaseError;
+use crate::events;
+use crate::mining::{self, MineLock};
+use crate::ttl;
+use crate::{GameContract, GameContractArgs, GameContractClient, StorageKey};
+
+// revenue shares are in basis points of each harvest
+pub const MAX_SHARE_BPS: u32 = 10_000;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Lease {
+    pub mine_id: u32,
+    pub owner: Address,
+    pub lessee: Address,        // harvests the mine while the lease runs
+    pub revenue_share_bps: u32, // owner's cut of every harvest
+    pub start_time: u64,
+    pub end_time: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DataKey {
+    Lease(u32),
+}
+
+// leases live in persistent storage, keyed by mine id, until the first
+// harvest after their end time has split the output made during the lease
+fn key(mine_id: u32) -> StorageKey {
+    StorageKey::Lease(DataKey::Lease(mine_id))
+}
+
+/// the lease on a mine, running or run out but not settled by a harvest yet
+pub(crate) fn load_lease(env: &Env, mine_id: u32) -> Option<Lease> {
+    ttl::get_persistent(env, &key(mine_id))
+}
+
+/// drop a lease once its output has been split
+pub(crate) fn close_lease(env: &Env, mine_id: u32) {
+    env.storage().persistent().remove(&key(mine_id));
+}
+
+/// the lease on a mine if it is still running, an expired lease hands the
+/// mine back to its owner without any call
+pub(crate) fn active_lease(env: &Env, mine_id: u32) -> Option<Lease> {
+    load_lease(env, mine_id).filter(|lease| env.ledger().timestamp() < lease.end_time)
+}
+
+#[contractimpl]
+impl GameContract {
+    /// lease a mine out for `duration` seconds. The lessee harvests it and
+    /// the owner keeps `revenue_share_bps` of every harvest, the mine can not
+    /// change hands until the lease ends
+    pub fn lease_mine(
+        env: Env,
+        mine_id: u32,
+        lessee: Address,
+        duration: u64,
+        revenue_share_bps: u32,
+    ) -> Result<(), LeaseError> {
+        let mut mine = mining::load_mine(&env, mine_id).ok_or(LeaseError::MineNotFound)?;
+        mine.owner.require_auth();
+
+        if duration == 0 {
+            return Err(LeaseError::InvalidDuration);
+        }
+        if revenue_share_bps > MAX_SHARE_BPS {
+            return Err(LeaseError::InvalidShare);
+        }
+        if lessee == mine.owner {
+            return Err(LeaseError::InvalidLessee);
+        }
+        if !mining::is_registered(&env, &lessee).map_err(|_| LeaseError::NotInitialized)? {
+            return Err(LeaseError::NotRegistered);
+        }
+
+        let start_time = env.ledger().timestamp();
+        let end_time = start_time.checked_add(duration).ok_or(LeaseError::InvalidDuration)?;
+        mining::lock_mine(&env, mine_id, MineLock::Lease(end_time)).map_err(|_| LeaseError::MineLocked)?;
+
+        // output so far, and what is left of an earlier lease, is paid out
+        // before the lessee takes over
+        mining::settle_output(&env, &mut mine).map_err(|_| LeaseError::NotInitialized)?;
+
+        let lease = Lease {
+            mine_id,
+            owner: mine.owner,
+            lessee,
+            revenue_share_bps,
+            start_time,
+            end_time,
+        };
+        ttl::set_persistent(&env, &key(mine_id), &lease);
+        events::mine_leased(&env, &lease);
+        Ok(())
+    }
+
+    /// hand a leased mine back to its owner before the lease runs out, lessee
+    /// only. Output made so far is still split at the next harvest
+    pub fn end_lease(env: Env, mine_id: u32) -> Result<(), LeaseError> {
+        let mut lease = active_lease(&env, mine_id).ok_or(LeaseError::LeaseNotFound)?;
+        lease.lessee.require_auth();
+
+        lease.end_time = env.ledger().timestamp();
+        ttl::set_persistent(&env, &key(mine_id), &lease);
+        mining::unlock_mine(&env, mine_id);
+        events::lease_ended(&env, &lease);
+        Ok(())
+    }
+
+    /// get the running lease on a mine
+    pub fn get_lease(env: Env, mine_id: u32) -> Option<Lease> {
+        active_lease(&env, mine_id)
+    }
+}
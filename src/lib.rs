@@ -0,0 +1,60 @@
+#![no_std]
+use soroban_sdk::{contract, contracttype};
+
+pub mod access;
+pub mod auction;
+pub mod config;
+pub mod crafting;
+pub mod delegation;
+pub mod equipment;
+pub mod errors;
+mod events;
+pub mod history;
+pub mod leaderboard;
+pub mod lease;
+pub mod marketplace;
+pub mod metals;
+pub mod mining;
+pub mod player;
+pub mod shares;
+pub mod token;
+pub mod ttl;
+
+mod test;
+
+pub use access::Role;
+pub use config::GameConfig;
+pub use errors::{
+    AccessError, AuctionError, CraftingError, DelegationError, EquipmentError, LeaseError,
+    MarketError, MetalError, MiningError, PlayerError, SharesError, TokenError,
+};
+pub use leaderboard::{LeaderboardEntry, LeaderboardKind, PlayerRank};
+pub use ttl::TtlConfig;
+
+// One contract type for the whole game. Each module adds its own
+// `#[contractimpl]` block, so every deployed instance exposes the same
+// interface and the same entry points are available in native tests.
+#[contract]
+pub struct GameContract;
+
+// Root storage key. Every module keeps its own `DataKey` enum and gets its own
+// namespace here, so both key spaces can live in the same contract instance.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum StorageKey {
+    Access(access::DataKey),
+    Auction(auction::DataKey),
+    Config(config::DataKey),
+    Crafting(crafting::DataKey),
+    Delegation(delegation::DataKey),
+    Equipment(equipment::DataKey),
+    History(history::DataKey),
+    Lease(lease::DataKey),
+    Market(marketplace::DataKey),
+    Metals(metals::DataKey),
+    Player(player::DataKey),
+    Mining(mining::DataKey),
+    Shares(shares::DataKey),
+    Token(token::DataKey),
+    Ttl(ttl::DataKey),
+}
@@ -0,0 +1,255 @@
+use soroban_sdk::{
+    contractimpl, contracttype, token::TokenClient, Address, Env, IntoVal, TryFromVal, Val, Vec,
+};
+
+use crate::access::{self, Role};
+use crate::errors::MarketError;
+use crate::events;
+use crate::mining::{self, MetalType, MineLock};
+use crate::token;
+use crate::ttl;
+use crate::{GameContract, GameContractArgs, GameContractClient, StorageKey};
+
+// fees are in basis points of the price
+pub const MAX_FEE_BPS: u32 = 10_000;
+
+// most listing ids scanned by one get_listings call
+pub const MAX_PAGE_SIZE: u32 = 50;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MarketConfig {
+    pub payment_token: Address, // SAC token listings are priced in
+    pub treasury: Address,      // receives the protocol fee
+    pub fee_bps: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ListingItem {
+    Mine(u32),
+    Metal(MetalType, i128),
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Listing {
+    pub id: u32,
+    pub seller: Address,
+    pub item: ListingItem,
+    pub price: i128,
+    pub created_at: u64,
+    pub terms: MarketConfig, // payment token, treasury and fee at listing time
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DataKey {
+    Config,
+    Listing(u32),
+    ListingCount,
+}
+
+// config and counters live in instance storage, listings in persistent storage
+fn load_listing(env: &Env, listing_id: u32) -> Option<Listing> {
+    ttl::get_persistent(env, &StorageKey::Market(DataKey::Listing(listing_id)))
+}
+
+fn open_listing(env: &Env, terms: MarketConfig, seller: &Address, item: ListingItem, price: i128) -> u32 {
+    let id = ttl::get_instance::<u32>(env, &StorageKey::Market(DataKey::ListingCount)).unwrap_or(0) + 1;
+    ttl::set_instance(env, &StorageKey::Market(DataKey::ListingCount), &id);
+
+    let listing = Listing {
+        id,
+        seller: seller.clone(),
+        item,
+        price,
+        created_at: env.ledger().timestamp(),
+        terms,
+    };
+    ttl::set_persistent(env, &StorageKey::Market(DataKey::Listing(id)), &listing);
+    events::listing_created(env, &listing);
+    id
+}
+
+fn close_listing(env: &Env, listing_id: u32) {
+    env.storage()
+        .persistent()
+        .remove(&StorageKey::Market(DataKey::Listing(listing_id)));
+}
+
+/// open entries among the ids `start_id` to `start_id + limit - 1`, ids are
+/// scanned instead of kept in a list so no entry grows with the open count
+pub(crate) fn page_ids<T>(
+    env: &Env,
+    start_id: u32,
+    limit: u32,
+    load: impl Fn(&Env, u32) -> Option<T>,
+) -> Vec<T>
+where
+    T: IntoVal<Env, Val> + TryFromVal<Env, Val>,
+{
+    let start = start_id.max(1);
+    let end = start.saturating_add(limit.min(MAX_PAGE_SIZE));
+
+    let mut entries = Vec::new(env);
+    for id in start..end {
+        if let Some(entry) = load(env, id) {
+            entries.push_back(entry);
+        }
+    }
+    entries
+}
+
+pub(crate) fn config(env: &Env) -> Result<MarketConfig, MarketError> {
+    ttl::get_instance(env, &StorageKey::Market(DataKey::Config)).ok_or(MarketError::NotConfigured)
+}
+
+/// pay `price` from `from` to `to` in the payment token, minus the protocol
+/// fee which goes to the treasury
+pub(crate) fn settle_payment(
+    env: &Env,
+    config: &MarketConfig,
+    from: &Address,
+    to: &Address,
+    price: i128,
+) -> Result<(), MarketError> {
+    let payment = TokenClient::new(env, &config.payment_token);
+    if payment.balance(from) < price {
+        return Err(MarketError::InsufficientFunds);
+    }
+
+    let fee = price * config.fee_bps as i128 / MAX_FEE_BPS as i128;
+    if fee > 0 {
+        payment.transfer(from, &config.treasury, &fee);
+    }
+    payment.transfer(from, to, &(price - fee));
+    Ok(())
+}
+
+// give an escrowed item to `to`
+fn release(env: &Env, item: &ListingItem, to: &Address) -> Result<(), MarketError> {
+    match item {
+        ListingItem::Mine(mine_id) => {
+            mining::unlock_mine(env, *mine_id);
+            let mut mine = mining::load_mine(env, *mine_id).ok_or(MarketError::MineNotFound)?;
+            if mine.owner != *to {
+                mining::change_owner(env, &mut mine, to).map_err(|_| MarketError::TransferFailed)?;
+            }
+        }
+        ListingItem::Metal(metal_type, amount) => {
+            token::move_balance(env, metal_type, &env.current_contract_address(), to, *amount)
+                .map_err(|_| MarketError::TransferFailed)?;
+        }
+    }
+    Ok(())
+}
+
+#[contractimpl]
+impl GameContract {
+    /// set payment token, treasury and fee, admin only
+    pub fn set_market_config(env: Env, admin: Address, config: MarketConfig) -> Result<(), MarketError> {
+        access::require_role(&env, &admin, Role::Admin).map_err(|_| MarketError::Unauthorized)?;
+        if config.fee_bps > MAX_FEE_BPS {
+            return Err(MarketError::InvalidConfig);
+        }
+
+        ttl::set_instance(&env, &StorageKey::Market(DataKey::Config), &config);
+        events::market_config_set(&env, &admin, &config);
+        Ok(())
+    }
+
+    /// get marketplace settings
+    pub fn get_market_config(env: Env) -> Option<MarketConfig> {
+        ttl::get_instance(&env, &StorageKey::Market(DataKey::Config))
+    }
+
+    /// list a mine for a fixed price, the mine is held in escrow until the
+    /// listing is bought or cancelled
+    pub fn list_mine(env: Env, mine_id: u32, price: i128) -> Result<u32, MarketError> {
+        let terms = config(&env)?;
+        let mine = mining::load_mine(&env, mine_id).ok_or(MarketError::MineNotFound)?;
+        mine.owner.require_auth();
+        if price <= 0 {
+            return Err(MarketError::InvalidPrice);
+        }
+        if mining::mine_lock(&env, mine_id).is_some() {
+            return Err(MarketError::MineLocked);
+        }
+
+        let id = open_listing(&env, terms, &mine.owner, ListingItem::Mine(mine_id), price);
+        mining::lock_mine(&env, mine_id, MineLock::Listing(id)).map_err(|_| MarketError::MineLocked)?;
+        Ok(id)
+    }
+
+    /// list an amount of metal for a fixed price, the metal is held by the
+    /// contract until the listing is bought or cancelled
+    pub fn list_metal(
+        env: Env,
+        seller: Address,
+        metal_type: MetalType,
+        amount: i128,
+        price: i128,
+    ) -> Result<u32, MarketError> {
+        let terms = config(&env)?;
+        seller.require_auth();
+        if amount <= 0 {
+            return Err(MarketError::InvalidAmount);
+        }
+        if price <= 0 {
+            return Err(MarketError::InvalidPrice);
+        }
+
+        token::move_balance(&env, &metal_type, &seller, &env.current_contract_address(), amount)
+            .map_err(|_| MarketError::InsufficientBalance)?;
+        Ok(open_listing(&env, terms, &seller, ListingItem::Metal(metal_type, amount), price))
+    }
+
+    /// take a listing down and return the escrowed item to the seller
+    pub fn cancel_listing(env: Env, listing_id: u32) -> Result<(), MarketError> {
+        let listing = load_listing(&env, listing_id).ok_or(MarketError::ListingNotFound)?;
+        listing.seller.require_auth();
+
+        close_listing(&env, listing_id);
+        release(&env, &listing.item, &listing.seller)?;
+        events::listing_cancelled(&env, &listing);
+        Ok(())
+    }
+
+    /// pay a listing's price and receive the item, in the token and with the
+    /// fee that were set when it was listed
+    pub fn buy_listing(env: Env, buyer: Address, listing_id: u32) -> Result<(), MarketError> {
+        buyer.require_auth();
+
+        let listing = load_listing(&env, listing_id).ok_or(MarketError::ListingNotFound)?;
+        if listing.seller == buyer {
+            return Err(MarketError::SelfPurchase);
+        }
+        if let ListingItem::Mine(_) = listing.item {
+            if !mining::is_registered(&env, &buyer).map_err(|_| MarketError::TransferFailed)? {
+                return Err(MarketError::NotRegistered);
+            }
+        }
+
+        close_listing(&env, listing_id);
+        settle_payment(&env, &listing.terms, &buyer, &listing.seller, listing.price)?;
+        release(&env, &listing.item, &buyer)?;
+        events::listing_sold(&env, &listing, &buyer);
+        Ok(())
+    }
+
+    /// get listing data
+    pub fn get_listing(env: Env, listing_id: u32) -> Option<Listing> {
+        load_listing(&env, listing_id)
+    }
+
+    /// get the open listings among `limit` ids from `start_id` on, oldest first
+    pub fn get_listings(env: Env, start_id: u32, limit: u32) -> Vec<Listing> {
+        page_ids(&env, start_id, limit, load_listing)
+    }
+
+    /// get number of listings ever created, ids run from 1 to this
+    pub fn get_listing_count(env: Env) -> u32 {
+        ttl::get_instance(&env, &StorageKey::Market(DataKey::ListingCount)).unwrap_or(0)
+    }
+}
@@ -0,0 +1,172 @@
+use soroban_sdk::{contractimpl, contracttype, panic_with_error, Address, Env, String, Vec};
+
+use crate::access::{self, Role};
+use crate::errors::MetalError;
+use crate::events;
+use crate::mining::MetalType;
+use crate::ttl;
+use crate::{GameContract, GameContractArgs, GameContractClient, StorageKey};
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Metal {
+    pub id: MetalType,
+    pub name: String,
+    pub symbol: String,
+    pub base_efficiency: u32,   // 1-100, mines roll within 10 points of it
+    pub base_capacity: u64,     // mines roll within 20% of it
+    pub base_rate: u64,         // output per hour per upgrade level at 100% efficiency
+    pub base_reserve: u64,      // mines roll within 25% of it
+    pub upgrade_cost: i128,     // metal burned per level on upgrade
+    pub rarity: u32,            // experience per unit harvested
+    pub max_upgrade_level: Option<u32>, // None follows the game's max_upgrade_level
+}
+
+// upper bounds keep stat rolls, accrual and experience well inside u64
+const MAX_CAPACITY: u64 = 1_000_000_000;
+const MAX_RATE: u64 = 1_000_000;
+const MAX_RESERVE: u64 = 1_000_000_000_000;
+const MAX_UPGRADE_COST: i128 = 1_000_000_000_000;
+const MAX_RARITY: u32 = 1_000;
+
+impl Metal {
+    fn is_valid(&self) -> bool {
+        (1..=100).contains(&self.base_efficiency)
+            && (1..=MAX_CAPACITY).contains(&self.base_capacity)
+            && (1..=MAX_RATE).contains(&self.base_rate)
+            && (1..=MAX_RESERVE).contains(&self.base_reserve)
+            && (0..=MAX_UPGRADE_COST).contains(&self.upgrade_cost)
+            && (1..=MAX_RARITY).contains(&self.rarity)
+            && self.max_upgrade_level.is_none_or(|level| (1..=100).contains(&level))
+            && !self.name.is_empty()
+            && !self.symbol.is_empty()
+    }
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DataKey {
+    Metal(MetalType),
+    MetalList,
+}
+
+// the registry is a setting and lives in instance storage
+fn save(env: &Env, metal: &Metal) {
+    ttl::set_instance(env, &StorageKey::Metals(DataKey::Metal(metal.id.clone())), metal);
+}
+
+/// look a metal up in the registry
+pub(crate) fn load_metal(env: &Env, metal_type: &MetalType) -> Option<Metal> {
+    ttl::get_instance(env, &StorageKey::Metals(DataKey::Metal(metal_type.clone())))
+}
+
+/// look up a metal that has to be registered, e.g. the metal of an existing mine
+pub(crate) fn require(env: &Env, metal_type: &MetalType) -> Metal {
+    load_metal(env, metal_type).unwrap_or_else(|| panic_with_error!(env, MetalError::MetalNotFound))
+}
+
+#[allow(clippy::too_many_arguments)]
+fn entry(
+    env: &Env,
+    id: MetalType,
+    name: &str,
+    symbol: &str,
+    base_efficiency: u32,
+    base_capacity: u64,
+    base_rate: u64,
+    base_reserve: u64,
+    upgrade_cost: i128,
+    rarity: u32,
+) -> Metal {
+    Metal {
+        id,
+        name: String::from_str(env, name),
+        symbol: String::from_str(env, symbol),
+        base_efficiency,
+        base_capacity,
+        base_rate,
+        base_reserve,
+        upgrade_cost,
+        rarity,
+        max_upgrade_level: None,
+    }
+}
+
+/// register the five original metals, called once from the constructor
+pub(crate) fn seed(env: &Env) {
+    let metals = [
+        entry(env, MetalType::Gold, "Gold", "GOLD", 45, 200, 10, 15_000, 40, 5),
+        entry(env, MetalType::Silver, "Silver", "SLVR", 60, 500, 25, 40_000, 100, 2),
+        entry(env, MetalType::Copper, "Copper", "COPR", 75, 800, 40, 80_000, 160, 1),
+        entry(env, MetalType::Iron, "Iron", "IRON", 80, 1000, 50, 100_000, 200, 1),
+        entry(env, MetalType::Platinum, "Platinum", "PLAT", 30, 100, 5, 5_000, 20, 10),
+    ];
+
+    let mut ids = Vec::new(env);
+    for metal in metals.iter() {
+        save(env, metal);
+        ids.push_back(metal.id.clone());
+    }
+    ttl::set_instance(env, &StorageKey::Metals(DataKey::MetalList), &ids);
+}
+
+fn require_admin(env: &Env, admin: &Address) -> Result<(), MetalError> {
+    access::require_role(env, admin, Role::Admin).map_err(|_| MetalError::Unauthorized)
+}
+
+#[contractimpl]
+impl GameContract {
+    /// register a new metal, admin only. New metals use `MetalType::Custom(id)`
+    pub fn add_metal(env: Env, admin: Address, metal: Metal) -> Result<(), MetalError> {
+        require_admin(&env, &admin)?;
+        if !metal.is_valid() {
+            return Err(MetalError::InvalidMetal);
+        }
+        if load_metal(&env, &metal.id).is_some() {
+            return Err(MetalError::MetalExists);
+        }
+
+        save(&env, &metal);
+        let mut ids: Vec<MetalType> = ttl::get_instance(&env, &StorageKey::Metals(DataKey::MetalList))
+            .unwrap_or(Vec::new(&env));
+        ids.push_back(metal.id.clone());
+        ttl::set_instance(&env, &StorageKey::Metals(DataKey::MetalList), &ids);
+
+        events::metal_added(&env, &metal);
+        Ok(())
+    }
+
+    /// change the stats of a registered metal, admin only. Existing mines
+    /// keep their rolled stats
+    pub fn update_metal(env: Env, admin: Address, metal: Metal) -> Result<(), MetalError> {
+        require_admin(&env, &admin)?;
+        if !metal.is_valid() {
+            return Err(MetalError::InvalidMetal);
+        }
+        if load_metal(&env, &metal.id).is_none() {
+            return Err(MetalError::MetalNotFound);
+        }
+
+        save(&env, &metal);
+        events::metal_updated(&env, &metal);
+        Ok(())
+    }
+
+    /// get a registered metal
+    pub fn get_metal(env: Env, metal_type: MetalType) -> Option<Metal> {
+        load_metal(&env, &metal_type)
+    }
+
+    /// get every registered metal in registration order
+    pub fn get_metals(env: Env) -> Vec<Metal> {
+        let ids: Vec<MetalType> = ttl::get_instance(&env, &StorageKey::Metals(DataKey::MetalList))
+            .unwrap_or(Vec::new(&env));
+        let mut metals = Vec::new(&env);
+        for id in ids.iter() {
+            if let Some(metal) = load_metal(&env, &id) {
+                metals.push_back(metal);
+            }
+        }
+        metals
+    }
+}
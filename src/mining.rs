@@ -1,5 +1,18 @@
-#![no_std]
-use soroban_sdk::{contract, contractimpl, contracttype, Address, Env, Map, String, Vec};
+use soroban_sdk::{contractimpl, contracttype, panic_with_error, token::TokenClient, Address, Env, Map, Vec};
+
+use crate::access::{self, Role};
+use crate::config;
+use crate::errors::MiningError;
+use crate::delegation;
+use crate::equipment;
+use crate::events;
+use crate::history;
+use crate::lease;
+use crate::metals::{self, Metal};
+use crate::shares;
+use crate::token;
+use crate::ttl;
+use crate::{GameContract, GameContractArgs, GameContractClient, StorageKey};
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -9,6 +22,7 @@ pub enum MetalType { // Types of metals can be mined
     Copper,
     Iron,
     Platinum,
+    Custom(u32), // added later through the metal registry
 }
 
 #[contracttype]
@@ -23,6 +37,12 @@ pub struct Mine {
     pub start_time: u64,        // Mining start time
     pub last_harvest: u64,      // Last harvest time
     pub upgrade_level: u32,     // Mine level
+    pub initial_reserve: u64,   // Ore in the ground at creation
+    pub reserve: u64,           // Ore left, the mine is exhausted at 0
+    pub seed: u64,              // Roll the starting stats were derived from
+    pub equipment: Vec<u32>,    // Equipped item ids
+    pub stockpile: u64,         // Output waiting for the next harvest, at most the capacity
+    pub last_accrual: u64,      // Production is credited in full hours from here
 }
 
 #[contracttype]
@@ -32,6 +52,107 @@ pub struct MinedResource {
     pub amount: u64,
     pub mined_at: u64,
     pub efficiency_bonus: u32,
+    pub roll: u64,              // Harvest roll, decides rich veins
+    pub rich_vein: bool,        // Output was doubled by a rich vein
+    pub mine_id: u32,
+    pub hours: u64,             // Production hours credited by the harvest
+}
+
+// outcome for one mine of a batch harvest
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum HarvestStatus {
+    Harvested(MinedResource),
+    NotFound,
+    NotAllowed, // caller may not harvest the mine
+    TooEarly,   // still within the cooldown
+    Exhausted,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct HarvestResult {
+    pub mine_id: u32,
+    pub status: HarvestStatus,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BatchHarvest {
+    pub results: Vec<HarvestResult>, // one per requested mine, in order
+    pub totals: Map<MetalType, u64>, // harvested amount per metal
+}
+
+// starting stats of a mine, a pure function of metal type and seed so any roll
+// can be replayed with `roll_mine_stats`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MineRoll {
+    pub efficiency: u32,
+    pub capacity: u64,
+    pub reserve: u64,
+}
+
+// splitmix64 step, turns the seed into an independent 64-bit draw per stat
+fn draw(seed: u64, stat: u64) -> u64 {
+    let mut z = seed.wrapping_add(stat.wrapping_mul(0x9e37_79b9_7f4a_7c15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// derive starting stats from a seed, each stat uses its own 64-bit draw.
+/// efficiency lands within 10 points of the metal's base, capacity within 20%
+/// and reserve within 25%
+pub fn roll_mine_stats(metal: &Metal, seed: u64) -> MineRoll {
+    let pick = |stat: u64, base: u64, spread: u64| {
+        let lo = base.saturating_sub(spread).max(1);
+        let hi = base.saturating_add(spread);
+        lo + draw(seed, stat) % (hi - lo + 1)
+    };
+
+    let efficiency = metal.base_efficiency as u64;
+    let capacity = metal.base_capacity;
+    let reserve = metal.base_reserve;
+    MineRoll {
+        efficiency: pick(1, efficiency, 10).min(100) as u32,
+        capacity: pick(2, capacity, capacity / 5),
+        reserve: pick(3, reserve, reserve / 4),
+    }
+}
+
+/// a harvest roll hits a rich vein 2% of the time
+pub fn is_rich_vein(roll: u64) -> bool {
+    roll % 100 < 2
+}
+
+// optional fee in an external token (e.g. the native XLM contract), charged
+// per level on top of the metal cost
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UpgradeFee {
+    pub token: Address,
+    pub recipient: Address,
+    pub amount_per_level: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UpgradeCost {
+    pub metal_type: MetalType,
+    pub metal_amount: i128,
+    pub fee_token: Option<Address>,
+    pub fee_amount: i128,
+}
+
+// what holds a mine in escrow, a locked mine can not change hands any other way
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum MineLock {
+    Listing(u32),
+    Auction(u32),
+    Lease(u64), // until the lease end time
+    Shared,
 }
 
 #[contracttype]
@@ -41,176 +162,622 @@ pub enum DataKey {
     PlayerMines(Address),
     MineCount,
     GlobalProduction(MetalType),
+    PlayerContract,
+    UpgradeFee,
+    Lock(u32),
+}
+
+// counters and config live in instance storage, mines and mine lists in
+// persistent storage
+pub(crate) fn load_mine(env: &Env, mine_id: u32) -> Option<Mine> {
+    ttl::get_persistent(env, &StorageKey::Mining(DataKey::Mine(mine_id)))
+}
+
+pub(crate) fn save_mine(env: &Env, mine: &Mine) {
+    ttl::set_persistent(env, &StorageKey::Mining(DataKey::Mine(mine.id)), mine);
+}
+
+fn load_player_mines(env: &Env, player: &Address) -> Vec<u32> {
+    ttl::get_persistent(env, &StorageKey::Mining(DataKey::PlayerMines(player.clone())))
+        .unwrap_or(Vec::new(env))
+}
+
+fn save_player_mines(env: &Env, player: &Address, mines: &Vec<u32>) {
+    ttl::set_persistent(env, &StorageKey::Mining(DataKey::PlayerMines(player.clone())), mines);
+}
+
+pub(crate) fn mine_lock(env: &Env, mine_id: u32) -> Option<MineLock> {
+    let lock = ttl::get_persistent(env, &StorageKey::Mining(DataKey::Lock(mine_id)))?;
+    match lock {
+        // leases release the mine on their own once they run out
+        MineLock::Lease(end_time) if env.ledger().timestamp() >= end_time => None,
+        lock => Some(lock),
+    }
+}
+
+pub(crate) fn lock_mine(env: &Env, mine_id: u32, lock: MineLock) -> Result<(), MiningError> {
+    if mine_lock(env, mine_id).is_some() {
+        return Err(MiningError::MineLocked);
+    }
+    ttl::set_persistent(env, &StorageKey::Mining(DataKey::Lock(mine_id)), &lock);
+    Ok(())
 }
 
-#[contract]
-pub struct MiningContract;
+pub(crate) fn unlock_mine(env: &Env, mine_id: u32) {
+    env.storage()
+        .persistent()
+        .remove(&StorageKey::Mining(DataKey::Lock(mine_id)));
+}
+
+// efficiency and capacity with the equipment bonus on top of the mine's own stats
+fn effective_stats(env: &Env, mine: &Mine) -> (u64, u64) {
+    let bonus = equipment::bonus(env, mine);
+    let efficiency = mine.efficiency as u64 * (10_000 + bonus.efficiency_bps as u64) / 10_000;
+    let capacity = mine.capacity * (10_000 + bonus.capacity_bps as u64) / 10_000;
+    (efficiency, capacity)
+}
+
+/// move the production of every full hour since the last accrual into the
+/// stockpile at the current stats, leftover minutes carry over. The stockpile
+/// stops growing at capacity. Call before the stats change, the caller saves
+/// the mine. Returns the hours credited. A lease that is not settled yet stops
+/// the accrual at its end time, the next harvest splits that output first
+pub(crate) fn accrue(env: &Env, mine: &mut Mine) -> u64 {
+    let now = env.ledger().timestamp();
+    let until = match lease::load_lease(env, mine.id) {
+        Some(lease) => now.min(lease.end_time),
+        None => now,
+    };
+    accrue_until(env, mine, until)
+}
+
+fn accrue_until(env: &Env, mine: &mut Mine, until: u64) -> u64 {
+    let hours = until.saturating_sub(mine.last_accrual) / 3600;
+    if hours == 0 {
+        return 0;
+    }
+
+    let (efficiency, capacity) = effective_stats(env, mine);
+    let rate = metals::require(env, &mine.metal_type).base_rate.saturating_mul(mine.upgrade_level as u64);
+    let produced = rate.saturating_mul(hours).saturating_mul(efficiency) / 100;
+    if mine.stockpile < capacity {
+        mine.stockpile = mine.stockpile.saturating_add(produced).min(capacity);
+    }
+    mine.last_accrual += hours * 3600;
+    hours
+}
+
+/// pay what a mine has produced so far to whoever it produced for, without
+/// cooldown, rich vein or wear. Called before a lease, a split or an owner
+/// change so the new side only gets output from then on. Saves the mine
+pub(crate) fn settle_output(env: &Env, mine: &mut Mine) -> Result<(), MiningError> {
+    GameContract::collect(env, mine, false)?;
+    save_mine(env, mine);
+    Ok(())
+}
+
+/// hand a mine to a new owner and keep mine lists and player records in sync.
+/// Output so far goes to the old owner and equipment stays with them. Saves
+/// the mine. The contract itself holds shared mines and has no mine list or
+/// player record
+pub(crate) fn change_owner(env: &Env, mine: &mut Mine, to: &Address) -> Result<(), MiningError> {
+    let players = players(env)?;
+    let from = mine.owner.clone();
+    let this = env.current_contract_address();
+
+    settle_output(env, mine)?;
+    equipment::unequip_all(env, mine);
+    mine.owner = to.clone();
+    save_mine(env, mine);
+
+    if from != this {
+        let mut from_mines = load_player_mines(env, &from);
+        if let Some(index) = from_mines.first_index_of(mine.id) {
+            from_mines.remove(index);
+        }
+        save_player_mines(env, &from, &from_mines);
+        players.remove_active_mine(&from, mine.id);
+    }
+    if *to != this {
+        let mut to_mines = load_player_mines(env, to);
+        to_mines.push_back(mine.id);
+        save_player_mines(env, to, &to_mines);
+        players.add_active_mine(to, mine.id);
+    }
+
+    events::mine_transferred(env, mine.id, &from, to);
+    Ok(())
+}
+
+// the player side that mirrors mining activity into player stats. A separate
+// player instance is reached with a contract call, when mining is linked to
+// its own instance the player functions are called directly, a contract can
+// not call itself
+pub(crate) enum Players<'a> {
+    Local(&'a Env),
+    Remote(GameContractClient<'a>),
+}
+
+pub(crate) fn players(env: &Env) -> Result<Players<'_>, MiningError> {
+    let address: Address = ttl::get_instance(env, &StorageKey::Mining(DataKey::PlayerContract))
+        .ok_or(MiningError::NotInitialized)?;
+    if address == env.current_contract_address() {
+        Ok(Players::Local(env))
+    } else {
+        Ok(Players::Remote(GameContractClient::new(env, &address)))
+    }
+}
+
+// a failed update aborts the call, the same way a failed contract call does
+impl Players<'_> {
+    pub(crate) fn is_registered(&self, player: &Address) -> bool {
+        match self {
+            Players::Local(env) => crate::player::load_player(env, player).is_some(),
+            Players::Remote(client) => client.get_player(player).is_some(),
+        }
+    }
+
+    fn add_active_mine(&self, player: &Address, mine_id: u32) {
+        match self {
+            Players::Local(env) => crate::player::add_mine(env, player, mine_id)
+                .unwrap_or_else(|err| panic_with_error!(env, err)),
+            Players::Remote(client) => client.add_active_mine(&client.env.current_contract_address(), player, &mine_id),
+        }
+    }
+
+    fn remove_active_mine(&self, player: &Address, mine_id: u32) {
+        match self {
+            Players::Local(env) => crate::player::remove_mine(env, player, mine_id)
+                .unwrap_or_else(|err| panic_with_error!(env, err)),
+            Players::Remote(client) => client.remove_active_mine(&client.env.current_contract_address(), player, &mine_id),
+        }
+    }
+
+    /// credit a harvested amount and the experience it is worth
+    pub(crate) fn credit(&self, player: &Address, amount: u64, experience: u64) {
+        match self {
+            Players::Local(env) => crate::player::add_total_mined(env, player, amount)
+                .and_then(|_| crate::player::add_experience(env, player, experience))
+                .unwrap_or_else(|err| panic_with_error!(env, err)),
+            Players::Remote(client) => {
+                let this = client.env.current_contract_address();
+                client.update_total_mined(&this, player, &amount);
+                client.update_experience(&this, player, &experience);
+            }
+        }
+    }
+}
+
+// who has to sign a harvest: the lessee of a leased mine, nobody for a
+// shared mine and the owner otherwise
+fn harvester(env: &Env, mine: &Mine) -> Option<Address> {
+    if let Some(lease) = lease::active_lease(env, mine.id) {
+        return Some(lease.lessee);
+    }
+    if shares::shared_mine(env, mine.id).is_some() {
+        return None;
+    }
+    Some(mine.owner.clone())
+}
+
+// whether `caller` may harvest the mine, itself or through a delegation
+fn may_harvest(env: &Env, mine: &Mine, caller: &Address) -> bool {
+    match harvester(env, mine) {
+        Some(harvester) => harvester == *caller || delegation::may_harvest(env, &harvester, caller, mine.id),
+        None => true,
+    }
+}
+
+/// credit a harvested amount to a player record, rarer metals give more
+/// experience per unit
+pub(crate) fn credit_stats(env: &Env, players: &Players, player: &Address, metal_type: &MetalType, amount: u64) {
+    let experience = amount * metals::require(env, metal_type).rarity as u64;
+    players.credit(player, amount, experience);
+}
+
+/// whether `player` has a record on the player contract, mines can only be
+/// handed to registered players
+pub(crate) fn is_registered(env: &Env, player: &Address) -> Result<bool, MiningError> {
+    Ok(players(env)?.is_registered(player))
+}
 
 #[contractimpl]
-impl MiningContract {
+impl GameContract {
+    /// link the player contract, admin only. Linking the contract's own
+    /// address runs players and mining in one instance
+    pub fn set_player_contract(env: Env, admin: Address, player_contract: Address) -> Result<(), MiningError> {
+        access::require_role(&env, &admin, Role::Admin).map_err(|_| MiningError::Unauthorized)?;
+
+        ttl::set_instance(&env, &StorageKey::Mining(DataKey::PlayerContract), &player_contract);
+        events::player_contract_set(&env, &player_contract);
+        Ok(())
+    }
+
+    /// get linked player contract
+    pub fn get_player_contract(env: Env) -> Option<Address> {
+        ttl::get_instance(&env, &StorageKey::Mining(DataKey::PlayerContract))
+    }
+
     // new mine creation
     pub fn create_mine(
         env: Env, 
         owner: Address, 
         metal_type: MetalType
-    ) -> Result<u32, &'static str> {
+    ) -> Result<u32, MiningError> {
         owner.require_auth();
+        let players = players(&env)?;
+        let metal = metals::load_metal(&env, &metal_type).ok_or(MiningError::UnknownMetal)?;
 
-        let mine_count: u32 = env.storage().instance()
-            .get(&DataKey::MineCount)
+        let mine_count: u32 = ttl::get_instance(&env, &StorageKey::Mining(DataKey::MineCount))
             .unwrap_or(0);
-        
+
         let mine_id = mine_count + 1;
-        
+
+        // roll starting stats
+        let seed: u64 = env.prng().gen();
+        let roll = roll_mine_stats(&metal, seed);
+
         let new_mine = Mine {
             id: mine_id,
             owner: owner.clone(),
             metal_type: metal_type.clone(),
-            efficiency: Self::calculate_base_efficiency(&metal_type),
-            capacity: Self::calculate_base_capacity(&metal_type),
+            efficiency: roll.efficiency,
+            capacity: roll.capacity,
             current_production: 0,
             start_time: env.ledger().timestamp(),
             last_harvest: env.ledger().timestamp(),
             upgrade_level: 1,
+            initial_reserve: roll.reserve,
+            reserve: roll.reserve,
+            seed,
+            equipment: Vec::new(&env),
+            stockpile: 0,
+            last_accrual: env.ledger().timestamp(),
         };
 
         // save mine
-        env.storage().instance().set(&DataKey::Mine(mine_id), &new_mine);
-        
+        save_mine(&env, &new_mine);
+        events::mine_created(&env, &new_mine);
+
         // update players mine list
-        let mut player_mines: Vec<u32> = env.storage().instance()
-            .get(&DataKey::PlayerMines(owner.clone()))
-            .unwrap_or(Vec::new(&env));
+        let mut player_mines = load_player_mines(&env, &owner);
         player_mines.push_back(mine_id);
-        env.storage().instance().set(&DataKey::PlayerMines(owner), &player_mines);
-        
+        save_player_mines(&env, &owner, &player_mines);
+
         // Update total mine count
-        env.storage().instance().set(&DataKey::MineCount, &mine_id);
+        ttl::set_instance(&env, &StorageKey::Mining(DataKey::MineCount), &mine_id);
+
+        // keep player record in sync
+        players.add_active_mine(&owner, mine_id);
 
         Ok(mine_id)
     }
 
     /// get mine data
     pub fn get_mine(env: Env, mine_id: u32) -> Option<Mine> {
-        env.storage().instance().get(&DataKey::Mine(mine_id))
+        load_mine(&env, mine_id)
     }
 
     /// get player mines
     pub fn get_player_mines(env: Env, player: Address) -> Vec<u32> {
-        env.storage().instance()
-            .get(&DataKey::PlayerMines(player))
-            .unwrap_or(Vec::new(&env))
+        load_player_mines(&env, &player)
     }
 
-    /// mining
-    pub fn harvest_mine(env: Env, mine_id: u32) -> Result<MinedResource, &'static str> {
-        let mut mine = env.storage().instance()
-            .get(&DataKey::Mine(mine_id))
-            .ok_or("Mine not found")?;
+    /// give a mine to another registered player
+    pub fn transfer_mine(env: Env, mine_id: u32, to: Address) -> Result<(), MiningError> {
+        let mut mine = load_mine(&env, mine_id)
+            .ok_or(MiningError::MineNotFound)?;
 
         mine.owner.require_auth();
+        if mine.owner == to {
+            return Err(MiningError::InvalidTransfer);
+        }
+        if mine_lock(&env, mine_id).is_some() {
+            return Err(MiningError::MineLocked);
+        }
 
-        let current_time = env.ledger().timestamp();
-        let time_since_last_harvest = current_time - mine.last_harvest;
-        
-        // has 1 hour passed?
-        if time_since_last_harvest < 3600 {
-            return Err("Must wait at least 1 hour between harvests");
+        change_owner(&env, &mut mine, &to)
+    }
+
+    /// mining, a leased mine is harvested by the lessee and a shared mine
+    /// by anyone
+    pub fn harvest_mine(env: Env, mine_id: u32) -> Result<MinedResource, MiningError> {
+        let mine = load_mine(&env, mine_id)
+            .ok_or(MiningError::MineNotFound)?;
+
+        if let Some(harvester) = harvester(&env, &mine) {
+            harvester.require_auth();
         }
+        Self::harvest(&env, mine)
+    }
 
-        // calculating procution amount (1 hour)
-        let hours_passed = time_since_last_harvest / 3600;
-        let base_production = Self::calculate_production_rate(&mine.metal_type, mine.upgrade_level);
-        let efficiency_multiplier = mine.efficiency as u64;
-        
-        let produced_amount = (base_production * hours_passed * efficiency_multiplier) / 100;
-        let final_amount = if produced_amount > mine.capacity {
-            mine.capacity
-        } else {
-            produced_amount
-        };
+    /// harvest every mine of a player that is ready, see harvest_many
+    pub fn harvest_all(env: Env, owner: Address) -> Result<BatchHarvest, MiningError> {
+        let mine_ids = load_player_mines(&env, &owner);
+        Self::harvest_many(env, owner, mine_ids)
+    }
 
-        // update mine
-        mine.current_production += final_amount;
-        mine.last_harvest = current_time;
-        env.storage().instance().set(&DataKey::Mine(mine_id), &mine);
+    /// harvest a mine on the owner's behalf, the output still goes to the
+    /// owner. Needs a delegation from whoever may harvest the mine
+    pub fn delegated_harvest(env: Env, delegate: Address, mine_id: u32) -> Result<MinedResource, MiningError> {
+        delegate.require_auth();
+        let mine = load_mine(&env, mine_id)
+            .ok_or(MiningError::MineNotFound)?;
 
-        // update global production
-        let mut global_production: u64 = env.storage().instance()
-            .get(&DataKey::GlobalProduction(mine.metal_type.clone()))
-            .unwrap_or(0);
-        global_production += final_amount;
-        env.storage().instance().set(
-            &DataKey::GlobalProduction(mine.metal_type.clone()), 
-            &global_production
-        );
+        if !may_harvest(&env, &mine, &delegate) {
+            return Err(MiningError::NotDelegated);
+        }
+        Self::harvest(&env, mine)
+    }
 
-        let mined_resource = MinedResource {
-            metal_type: mine.metal_type.clone(),
-            amount: final_amount,
-            mined_at: current_time,
-            efficiency_bonus: mine.efficiency,
-        };
+    /// harvest a list of mines with one signature, the caller can be the
+    /// owner or a delegate. Mines the caller may not harvest, still within
+    /// the cooldown or exhausted are skipped instead of failing the call
+    pub fn harvest_many(env: Env, caller: Address, mine_ids: Vec<u32>) -> Result<BatchHarvest, MiningError> {
+        caller.require_auth();
 
-        Ok(mined_resource)
+        let mut results = Vec::new(&env);
+        let mut totals = Map::new(&env);
+        for mine_id in mine_ids.iter() {
+            let status = match load_mine(&env, mine_id) {
+                None => HarvestStatus::NotFound,
+                Some(mine) if !may_harvest(&env, &mine, &caller) => HarvestStatus::NotAllowed,
+                Some(mine) => match Self::harvest(&env, mine) {
+                    Ok(resource) => {
+                        let total = totals.get(resource.metal_type.clone()).unwrap_or(0);
+                        totals.set(resource.metal_type.clone(), total + resource.amount);
+                        HarvestStatus::Harvested(resource)
+                    }
+                    Err(MiningError::HarvestTooEarly) => HarvestStatus::TooEarly,
+                    Err(MiningError::MineExhausted) => HarvestStatus::Exhausted,
+                    Err(err) => return Err(err),
+                },
+            };
+            results.push_back(HarvestResult { mine_id, status });
+        }
+        Ok(BatchHarvest { results, totals })
     }
 
-    /// upgrade mine
-    pub fn upgrade_mine(env: Env, mine_id: u32) -> Result<(), &'static str> {
-        let mut mine = env.storage().instance()
-            .get(&DataKey::Mine(mine_id))
-            .ok_or("Mine not found")?;
+    /// get what holds a mine in escrow, if anything
+    pub fn get_mine_lock(env: Env, mine_id: u32) -> Option<MineLock> {
+        mine_lock(&env, mine_id)
+    }
 
-        mine.owner.require_auth();
+    /// get ore left in a mine
+    pub fn get_remaining_reserve(env: Env, mine_id: u32) -> Result<u64, MiningError> {
+        let mine = load_mine(&env, mine_id)
+            .ok_or(MiningError::MineNotFound)?;
+        Ok(mine.reserve)
+    }
+
+    /// get what a harvest would return right now, before rich veins
+    pub fn pending_output(env: Env, mine_id: u32) -> Result<u64, MiningError> {
+        let mut mine = load_mine(&env, mine_id)
+            .ok_or(MiningError::MineNotFound)?;
+        accrue_until(&env, &mut mine, env.ledger().timestamp());
+        Ok(Self::apply_reserve(&mine, mine.stockpile))
+    }
+
+    /// set or clear the token fee charged on upgrades, admin only
+    pub fn set_upgrade_fee(env: Env, admin: Address, fee: Option<UpgradeFee>) -> Result<(), MiningError> {
+        access::require_role(&env, &admin, Role::Admin).map_err(|_| MiningError::Unauthorized)?;
+        if fee.as_ref().is_some_and(|fee| fee.amount_per_level < 0) {
+            return Err(MiningError::InvalidUpgradeFee);
+        }
+
+        match &fee {
+            Some(fee) => ttl::set_instance(&env, &StorageKey::Mining(DataKey::UpgradeFee), fee),
+            None => ttl::remove_instance(&env, &StorageKey::Mining(DataKey::UpgradeFee)),
+        }
+        events::upgrade_fee_set(&env, &admin, &fee);
+        Ok(())
+    }
 
-        if mine.upgrade_level >= 10 {
-            return Err("Maximum upgrade level reached");
+    /// get token fee charged on upgrades
+    pub fn get_upgrade_fee(env: Env) -> Option<UpgradeFee> {
+        ttl::get_instance(&env, &StorageKey::Mining(DataKey::UpgradeFee))
+    }
+
+    /// get what the next upgrade of a mine costs
+    pub fn get_upgrade_cost(env: Env, mine_id: u32) -> Result<UpgradeCost, MiningError> {
+        let mine = load_mine(&env, mine_id)
+            .ok_or(MiningError::MineNotFound)?;
+        let metal = metals::require(&env, &mine.metal_type);
+        let fee = Self::get_upgrade_fee(env);
+
+        Ok(UpgradeCost {
+            metal_type: mine.metal_type.clone(),
+            metal_amount: metal.upgrade_cost * mine.upgrade_level as i128,
+            fee_token: fee.as_ref().map(|fee| fee.token.clone()),
+            fee_amount: fee.map_or(0, |fee| fee.amount_per_level * mine.upgrade_level as i128),
+        })
+    }
+
+    /// upgrade mine, paid with the mine's own metal and the optional token fee
+    pub fn upgrade_mine(env: Env, mine_id: u32) -> Result<(), MiningError> {
+        let mut mine = load_mine(&env, mine_id)
+            .ok_or(MiningError::MineNotFound)?;
+
+        // shareholders elect who upgrades a shared mine
+        let payer = shares::shared_mine(&env, mine_id)
+            .map(|shared| shared.upgrader)
+            .unwrap_or(mine.owner.clone());
+        payer.require_auth();
+
+        if mine.reserve == 0 {
+            return Err(MiningError::MineExhausted);
+        }
+        let metal = metals::require(&env, &mine.metal_type);
+        let game = config::game_config(&env);
+        if mine.upgrade_level >= metal.max_upgrade_level.unwrap_or(game.max_upgrade_level) {
+            return Err(MiningError::MaxUpgradeLevel);
+        }
+
+        // pay for the upgrade
+        let cost = Self::get_upgrade_cost(env.clone(), mine_id)?;
+        if token::balance(&env, &cost.metal_type, &payer) < cost.metal_amount {
+            return Err(MiningError::CannotAffordUpgrade);
+        }
+        if let Some(fee) = Self::get_upgrade_fee(env.clone()) {
+            let fee_token = TokenClient::new(&env, &fee.token);
+            if fee_token.balance(&payer) < cost.fee_amount {
+                return Err(MiningError::CannotAffordUpgrade);
+            }
+            fee_token.transfer(&payer, &fee.recipient, &cost.fee_amount);
         }
+        token::burn(&env, &cost.metal_type, &payer, cost.metal_amount)
+            .map_err(|_| MiningError::CannotAffordUpgrade)?;
 
+        accrue(&env, &mut mine);
         mine.upgrade_level += 1;
-        mine.efficiency += 5; // Her seviyede %5 verimlilik artışı
-        mine.capacity += Self::calculate_base_capacity(&mine.metal_type) / 10; // %10 kapasite artışı
+        mine.efficiency += game.upgrade_efficiency_bonus;
+        mine.capacity += metal.base_capacity * game.upgrade_capacity_bps as u64 / 10_000;
 
-        env.storage().instance().set(&DataKey::Mine(mine_id), &mine);
+        save_mine(&env, &mine);
+        events::mine_upgraded(&env, &mine);
         Ok(())
     }
 
     /// get global production data
     pub fn get_global_production(env: Env, metal_type: MetalType) -> u64 {
-        env.storage().instance()
-            .get(&DataKey::GlobalProduction(metal_type))
-            .unwrap_or(0)
+        ttl::get_instance(&env, &StorageKey::Mining(DataKey::GlobalProduction(metal_type))).unwrap_or(0)
     }
 
     // Helper Functions
-    fn calculate_base_efficiency(metal_type: &MetalType) -> u32 {
-        match metal_type {
-            MetalType::Iron => 80,
-            MetalType::Copper => 75,
-            MetalType::Silver => 60,
-            MetalType::Gold => 45,
-            MetalType::Platinum => 30,
-        }
+
+    // below 20% of the initial reserve the yield shrinks with the ore left,
+    // and a harvest never takes more than what is in the ground
+    fn apply_reserve(mine: &Mine, amount: u64) -> u64 {
+        let low_mark = mine.initial_reserve / 5;
+        let amount = if mine.reserve < low_mark && amount > 0 {
+            ((amount as u128 * mine.reserve as u128 / low_mark as u128) as u64).max(1)
+        } else {
+            amount
+        };
+        amount.min(mine.reserve)
     }
 
-    fn calculate_base_capacity(metal_type: &MetalType) -> u64 {
-        match metal_type {
-            MetalType::Iron => 1000,
-            MetalType::Copper => 800,
-            MetalType::Silver => 500,
-            MetalType::Gold => 200,
-            MetalType::Platinum => 100,
+    // harvest a mine, the caller checks who may do so. A harvest with nothing
+    // to collect counts as too early
+    fn harvest(env: &Env, mut mine: Mine) -> Result<MinedResource, MiningError> {
+        if mine.reserve == 0 {
+            return Err(MiningError::MineExhausted);
         }
+
+        let current_time = env.ledger().timestamp();
+        let time_since_last_harvest = current_time - mine.last_harvest;
+        
+        // has the cooldown passed?
+        if time_since_last_harvest < config::game_config(env).harvest_cooldown {
+            return Err(MiningError::HarvestTooEarly);
+        }
+
+        let Some(mined_resource) = Self::collect(env, &mut mine, true)? else {
+            // a run-out lease may have been closed, keep the mine in step
+            save_mine(env, &mine);
+            return Err(MiningError::HarvestTooEarly);
+        };
+        mine.last_harvest = current_time;
+        equipment::wear(env, &mut mine);
+        save_mine(env, &mine);
+
+        Ok(mined_resource)
     }
 
-    fn calculate_production_rate(metal_type: &MetalType, upgrade_level: u32) -> u64 {
-        let base_rate = match metal_type {
-            MetalType::Iron => 50,
-            MetalType::Copper => 40,
-            MetalType::Silver => 25,
-            MetalType::Gold => 10,
-            MetalType::Platinum => 5,
+    // empty the stockpile and credit it. Output from a lease is split between
+    // the lease's owner and lessee, even once the lease has run out, the rest
+    // goes to the owner or the shareholders. Rolls for a rich vein if asked.
+    // Returns None when there is nothing to collect, the caller saves the mine
+    fn collect(env: &Env, mine: &mut Mine, roll_vein: bool) -> Result<Option<MinedResource>, MiningError> {
+        let players = players(env)?;
+        let current_time = env.ledger().timestamp();
+
+        // production up to the lease end first, then whatever came after it
+        let lease = lease::load_lease(env, mine.id);
+        let mut hours_passed = 0;
+        let mut lease_stock = 0;
+        if let Some(lease) = &lease {
+            hours_passed += accrue_until(env, mine, current_time.min(lease.end_time));
+            lease_stock = mine.stockpile;
+            if current_time >= lease.end_time {
+                lease::close_lease(env, mine.id);
+            }
+        }
+        hours_passed += accrue(env, mine);
+
+        let stock = mine.stockpile;
+        if stock == 0 || mine.reserve == 0 {
+            return Ok(None);
+        }
+        let (efficiency_multiplier, _) = effective_stats(env, mine);
+
+        // rare rich veins double the output
+        let roll: u64 = if roll_vein { env.prng().gen() } else { 0 };
+        let rich_vein = roll_vein && is_rich_vein(roll);
+        let final_amount = if rich_vein { stock * 2 } else { stock };
+        let final_amount = Self::apply_reserve(mine, final_amount);
+
+        // update mine
+        mine.current_production += final_amount;
+        mine.reserve -= final_amount;
+        mine.stockpile = 0;
+        if mine.reserve == 0 {
+            events::mine_exhausted(env, mine);
+        }
+
+        // update global production
+        let production_key = StorageKey::Mining(DataKey::GlobalProduction(mine.metal_type.clone()));
+        let mut global_production: u64 = ttl::get_instance(env, &production_key).unwrap_or(0);
+        global_production += final_amount;
+        ttl::set_instance(env, &production_key, &global_production);
+
+        let mined_resource = MinedResource {
+            metal_type: mine.metal_type.clone(),
+            amount: final_amount,
+            mined_at: current_time,
+            efficiency_bonus: u32::try_from(efficiency_multiplier).unwrap_or(u32::MAX),
+            roll,
+            rich_vein,
+            mine_id: mine.id,
+            hours: hours_passed,
         };
-        base_rate * upgrade_level as u64
+        events::mine_harvested(env, mine, &mined_resource);
+        history::record_mine(env, &mined_resource);
+
+        // split the lease's part of the output, mint the rest to the owner or
+        // credit it to the shareholders
+        let lease_amount = (final_amount as u128 * lease_stock as u128 / stock as u128) as u64;
+        if let Some(lease) = lease {
+            let owner_amount = lease_amount * lease.revenue_share_bps as u64 / lease::MAX_SHARE_BPS as u64;
+            let lessee_amount = lease_amount - owner_amount;
+            Self::credit_harvest(env, &players, &mined_resource, &lease.owner, owner_amount);
+            Self::credit_harvest(env, &players, &mined_resource, &lease.lessee, lessee_amount);
+            events::lease_harvested(env, &lease, owner_amount, lessee_amount);
+        }
+        let rest = MinedResource { amount: final_amount - lease_amount, ..mined_resource.clone() };
+        match shares::shared_mine(env, mine.id) {
+            Some(shared) => shares::distribute(env, &players, &shared, &rest),
+            None => Self::credit_harvest(env, &players, &rest, &mine.owner, rest.amount),
+        }
+
+        Ok(Some(mined_resource))
+    }
+
+    // mint a player's part of a harvest, credit player stats and record the
+    // part in the player's history
+    fn credit_harvest(
+        env: &Env,
+        players: &Players,
+        resource: &MinedResource,
+        player: &Address,
+        amount: u64,
+    ) {
+        if amount == 0 {
+            return;
+        }
+        let metal_type = &resource.metal_type;
+        token::mint(env, metal_type, player, amount as i128);
+        history::record_player(env, player, &MinedResource { amount, ..resource.clone() });
+        credit_stats(env, players, player, metal_type, amount);
     }
-}
\ No newline at end of file
+}
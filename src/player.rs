@@ -1,5 +1,12 @@
-#![no_std]
-use soroban_sdk::{contract, contractimpl, contracttype, Address, Env, Map, String, Vec};
+use soroban_sdk::{contractimpl, contracttype, Address, Env, String, Vec};
+
+use crate::access::{self, Role};
+use crate::config;
+use crate::errors::PlayerError;
+use crate::events;
+use crate::leaderboard::{self, LeaderboardKind};
+use crate::ttl;
+use crate::{GameContract, GameContractArgs, GameContractClient, StorageKey};
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -18,19 +25,102 @@ pub struct Player {
 pub enum DataKey {
     Player(Address),
     PlayerCount,
-    Leaderboard,
+    Leaderboard(LeaderboardKind),
+}
+
+// counters live in instance storage, player records in persistent storage
+fn has_player(env: &Env, player: &Address) -> bool {
+    ttl::has_persistent(env, &StorageKey::Player(DataKey::Player(player.clone())))
+}
+
+pub(crate) fn load_player(env: &Env, player: &Address) -> Option<Player> {
+    ttl::get_persistent(env, &StorageKey::Player(DataKey::Player(player.clone())))
+}
+
+fn save_player(env: &Env, player_data: &Player) {
+    ttl::set_persistent(
+        env,
+        &StorageKey::Player(DataKey::Player(player_data.address.clone())),
+        player_data,
+    );
 }
 
-#[contract]
-pub struct PlayerContract;
+fn require_role(env: &Env, caller: &Address, role: Role) -> Result<(), PlayerError> {
+    access::require_role(env, caller, role).map_err(|_| PlayerError::Unauthorized)
+}
+
+// stat updates behind the GameOperator entry points, mining calls them
+// directly when it runs in the same instance
+
+pub(crate) fn add_experience(env: &Env, player: &Address, exp_gained: u64) -> Result<(), PlayerError> {
+    let mut player_data = load_player(env, player)
+        .ok_or(PlayerError::PlayerNotFound)?;
+
+    player_data.experience += exp_gained;
+    player_data.last_activity = env.ledger().timestamp();
+    events::experience_gained(env, player, exp_gained, player_data.experience);
+
+    // calculate level (simple formula: every experience_per_level exp = 1 level)
+    let new_level = (player_data.experience / config::game_config(env).experience_per_level) + 1;
+    if new_level > player_data.level as u64 {
+        events::level_up(env, player, player_data.level, new_level as u32);
+        player_data.level = new_level as u32;
+    }
+
+    save_player(env, &player_data);
+    leaderboard::record(env, LeaderboardKind::Experience, player, player_data.experience);
+    leaderboard::record(env, LeaderboardKind::Level, player, player_data.level as u64);
+    Ok(())
+}
+
+pub(crate) fn add_mine(env: &Env, player: &Address, mine_id: u32) -> Result<(), PlayerError> {
+    let mut player_data = load_player(env, player)
+        .ok_or(PlayerError::PlayerNotFound)?;
+
+    player_data.active_mines.push_back(mine_id);
+    player_data.last_activity = env.ledger().timestamp();
+
+    save_player(env, &player_data);
+    events::active_mine_added(env, player, mine_id);
+    Ok(())
+}
+
+pub(crate) fn remove_mine(env: &Env, player: &Address, mine_id: u32) -> Result<(), PlayerError> {
+    let mut player_data = load_player(env, player)
+        .ok_or(PlayerError::PlayerNotFound)?;
+
+    if let Some(index) = player_data.active_mines.first_index_of(mine_id) {
+        player_data.active_mines.remove(index);
+    }
+    player_data.last_activity = env.ledger().timestamp();
+
+    save_player(env, &player_data);
+    events::active_mine_removed(env, player, mine_id);
+    Ok(())
+}
+
+pub(crate) fn add_total_mined(env: &Env, player: &Address, amount: u64) -> Result<(), PlayerError> {
+    let mut player_data = load_player(env, player)
+        .ok_or(PlayerError::PlayerNotFound)?;
+
+    player_data.total_mined += amount;
+    player_data.last_activity = env.ledger().timestamp();
+
+    save_player(env, &player_data);
+    events::total_mined_updated(env, player, amount, player_data.total_mined);
+    leaderboard::record(env, LeaderboardKind::TotalMined, player, player_data.total_mined);
+    Ok(())
+}
 
 #[contractimpl]
-impl PlayerContract {
+impl GameContract {
     //register new player
-    pub fn register_player(env: Env, player: Address, username: String) -> Result<(), &'static str> {
+    pub fn register_player(env: Env, player: Address, username: String) -> Result<(), PlayerError> {
+        player.require_auth();
+
         // check if player is already registered
-        if env.storage().instance().has(&DataKey::Player(player.clone())) {
-            return Err("Player already registered");
+        if has_player(&env, &player) {
+            return Err(PlayerError::AlreadyRegistered);
         }
 
         let new_player = Player {
@@ -44,80 +134,93 @@ impl PlayerContract {
         };
 
         // save player
-        env.storage().instance().set(&DataKey::Player(player.clone()), &new_player);
-        
+        save_player(&env, &new_player);
+
         // update total player count
-        let mut player_count: u32 = env.storage().instance()
-            .get(&DataKey::PlayerCount)
+        let mut player_count: u32 = ttl::get_instance(&env, &StorageKey::Player(DataKey::PlayerCount))
             .unwrap_or(0);
         player_count += 1;
-        env.storage().instance().set(&DataKey::PlayerCount, &player_count);
+        ttl::set_instance(&env, &StorageKey::Player(DataKey::PlayerCount), &player_count);
+
+        events::player_registered(&env, &player, &username);
 
         Ok(())
     }
 
-    // get player data
-    pub fn get_player(env: Env, player: Address) -> Option<Player> {
-        env.storage().instance().get(&DataKey::Player(player))
-    }
+    // change username
+    pub fn update_username(env: Env, player: Address, username: String) -> Result<(), PlayerError> {
+        player.require_auth();
 
-    // update player experience
-    pub fn update_experience(env: Env, player: Address, exp_gained: u64) -> Result<(), &'static str> {
-        let mut player_data = env.storage().instance()
-            .get(&DataKey::Player(player.clone()))
-            .ok_or("Player not found")?;
+        let mut player_data = load_player(&env, &player)
+            .ok_or(PlayerError::PlayerNotFound)?;
 
-        player_data.experience += exp_gained;
+        player_data.username = username.clone();
         player_data.last_activity = env.ledger().timestamp();
-        
-        // calculate level (simple formula: every 1000 exp = 1 level)
-        let new_level = (player_data.experience / 1000) + 1;
-        if new_level > player_data.level {
-            player_data.level = new_level as u32;
-        }
 
-        env.storage().instance().set(&DataKey::Player(player), &player_data);
+        save_player(&env, &player_data);
+        events::username_changed(&env, &player, &username);
         Ok(())
     }
 
-    // add active mine
-    pub fn add_active_mine(env: Env, player: Address, mine_id: u32) -> Result<(), &'static str> {
-        let mut player_data = env.storage().instance()
-            .get(&DataKey::Player(player.clone()))
-            .ok_or("Player not found")?;
+    // reset a username, moderators only
+    pub fn moderate_username(
+        env: Env,
+        moderator: Address,
+        player: Address,
+        username: String,
+    ) -> Result<(), PlayerError> {
+        require_role(&env, &moderator, Role::Moderator)?;
+
+        let mut player_data = load_player(&env, &player)
+            .ok_or(PlayerError::PlayerNotFound)?;
+
+        player_data.username = username.clone();
+        save_player(&env, &player_data);
+        events::username_changed(&env, &player, &username);
+        Ok(())
+    }
 
-        player_data.active_mines.push_back(mine_id);
-        player_data.last_activity = env.ledger().timestamp();
+    // get player data
+    pub fn get_player(env: Env, player: Address) -> Option<Player> {
+        load_player(&env, &player)
+    }
 
-        env.storage().instance().set(&DataKey::Player(player), &player_data);
-        Ok(())
+    // update player experience
+    pub fn update_experience(env: Env, caller: Address, player: Address, exp_gained: u64) -> Result<(), PlayerError> {
+        require_role(&env, &caller, Role::GameOperator)?;
+        add_experience(&env, &player, exp_gained)
     }
 
-    // update total mining amount
-    pub fn update_total_mined(env: Env, player: Address, amount: u64) -> Result<(), &'static str> {
-        let mut player_data = env.storage().instance()
-            .get(&DataKey::Player(player.clone()))
-            .ok_or("Player not found")?;
+    // add active mine
+    pub fn add_active_mine(env: Env, caller: Address, player: Address, mine_id: u32) -> Result<(), PlayerError> {
+        require_role(&env, &caller, Role::GameOperator)?;
+        add_mine(&env, &player, mine_id)
+    }
 
-        player_data.total_mined += amount;
-        player_data.last_activity = env.ledger().timestamp();
+    // remove active mine
+    pub fn remove_active_mine(env: Env, caller: Address, player: Address, mine_id: u32) -> Result<(), PlayerError> {
+        require_role(&env, &caller, Role::GameOperator)?;
+        remove_mine(&env, &player, mine_id)
+    }
 
-        env.storage().instance().set(&DataKey::Player(player), &player_data);
-        Ok(())
+    // update total mining amount
+    pub fn update_total_mined(env: Env, caller: Address, player: Address, amount: u64) -> Result<(), PlayerError> {
+        require_role(&env, &caller, Role::GameOperator)?;
+        add_total_mined(&env, &player, amount)
     }
 
     // get total player count
     pub fn get_player_count(env: Env) -> u32 {
-        env.storage().instance().get(&DataKey::PlayerCount).unwrap_or(0)
+        ttl::get_instance(&env, &StorageKey::Player(DataKey::PlayerCount)).unwrap_or(0)
     }
 
-    // Is player active? (Last 24 hours)
+    // Is player active? (within the activity window, 24 hours by default)
     pub fn is_player_active(env: Env, player: Address) -> bool {
-        if let Some(player_data) = env.storage().instance().get(&DataKey::Player(player)) {
+        if let Some(player_data) = load_player(&env, &player) {
             let current_time = env.ledger().timestamp();
-            let day_in_seconds = 24 * 60 * 60;
-            return current_time - player_data.last_activity < day_in_seconds;
+            let activity_window = config::game_config(&env).activity_window;
+            return current_time - player_data.last_activity < activity_window;
         }
         false
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,264 @@
+use soroban_sdk::{contractimpl, contracttype, Address, Env, Map};
+
+use crate::errors::SharesError;
+use crate::events;
+use crate::history;
+use crate::mining::{self, MetalType, MineLock, MinedResource, Players};
+use crate::token;
+use crate::ttl;
+use crate::{GameContract, GameContractArgs, GameContractClient, StorageKey};
+
+// most addresses holding shares of one mine, harvests pay every holder
+pub const MAX_SHAREHOLDERS: u32 = 20;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SharedMine {
+    pub mine_id: u32,
+    pub total_shares: u32,
+    pub holders: Map<Address, u32>, // shares held per address
+    pub upgrader: Address,          // may trigger and pays for upgrades
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DataKey {
+    Shared(u32),
+    Vote(u32, Address),
+    Claimable(Address, MetalType),
+}
+
+// shared mines, votes and claimable balances live in persistent storage
+fn load_shared(env: &Env, mine_id: u32) -> Option<SharedMine> {
+    ttl::get_persistent(env, &StorageKey::Shares(DataKey::Shared(mine_id)))
+}
+
+fn save_shared(env: &Env, shared: &SharedMine) {
+    ttl::set_persistent(env, &StorageKey::Shares(DataKey::Shared(shared.mine_id)), shared);
+}
+
+fn load_vote(env: &Env, mine_id: u32, holder: &Address) -> Option<Address> {
+    ttl::get_persistent(env, &StorageKey::Shares(DataKey::Vote(mine_id, holder.clone())))
+}
+
+fn remove_vote(env: &Env, mine_id: u32, holder: &Address) {
+    env.storage()
+        .persistent()
+        .remove(&StorageKey::Shares(DataKey::Vote(mine_id, holder.clone())));
+}
+
+fn claimable(env: &Env, holder: &Address, metal_type: &MetalType) -> i128 {
+    ttl::get_persistent(env, &StorageKey::Shares(DataKey::Claimable(holder.clone(), metal_type.clone())))
+        .unwrap_or(0)
+}
+
+fn set_claimable(env: &Env, holder: &Address, metal_type: &MetalType, amount: i128) {
+    ttl::set_persistent(
+        env,
+        &StorageKey::Shares(DataKey::Claimable(holder.clone(), metal_type.clone())),
+        &amount,
+    );
+}
+
+/// the share register of a mine, if it has been split
+pub(crate) fn shared_mine(env: &Env, mine_id: u32) -> Option<SharedMine> {
+    load_shared(env, mine_id)
+}
+
+/// mint a harvest to the contract and credit it to the holders pro rata, the
+/// rounding dust goes to the largest holder. Each holder's part goes into
+/// their harvest history and, for registered players, their stats
+pub(crate) fn distribute(env: &Env, players: &Players, shared: &SharedMine, resource: &MinedResource) {
+    let amount = resource.amount;
+    if amount == 0 {
+        return;
+    }
+    let metal_type = &resource.metal_type;
+    token::mint(env, metal_type, &env.current_contract_address(), amount as i128);
+
+    let mut parts: Map<Address, u64> = Map::new(env);
+    let mut paid: u64 = 0;
+    let mut largest: Option<Address> = None;
+    let mut most: u32 = 0;
+    for (holder, shares) in shared.holders.iter() {
+        let part = amount * shares as u64 / shared.total_shares as u64;
+        parts.set(holder.clone(), part);
+        paid += part;
+        if shares > most {
+            largest = Some(holder);
+            most = shares;
+        }
+    }
+    if let Some(holder) = largest {
+        parts.set(holder.clone(), parts.get(holder).unwrap_or(0) + amount - paid);
+    }
+
+    for (holder, part) in parts.iter() {
+        if part == 0 {
+            continue;
+        }
+        set_claimable(env, &holder, metal_type, claimable(env, &holder, metal_type) + part as i128);
+        history::record_player(env, &holder, &MinedResource { amount: part, ..resource.clone() });
+        if players.is_registered(&holder) {
+            mining::credit_stats(env, players, &holder, metal_type, part);
+        }
+    }
+}
+
+#[contractimpl]
+impl GameContract {
+    /// split a mine into `total_shares` shares, all held by the owner at
+    /// first. The contract holds the mine until one address holds every
+    /// share again and redeems it
+    pub fn fractionalize_mine(env: Env, mine_id: u32, total_shares: u32) -> Result<(), SharesError> {
+        let mut mine = mining::load_mine(&env, mine_id).ok_or(SharesError::MineNotFound)?;
+        let owner = mine.owner.clone();
+        owner.require_auth();
+
+        if total_shares < 2 {
+            return Err(SharesError::InvalidShares);
+        }
+        mining::lock_mine(&env, mine_id, MineLock::Shared).map_err(|_| SharesError::MineLocked)?;
+        mining::change_owner(&env, &mut mine, &env.current_contract_address())
+            .map_err(|_| SharesError::TransferFailed)?;
+
+        let mut holders = Map::new(&env);
+        holders.set(owner.clone(), total_shares);
+        let shared = SharedMine {
+            mine_id,
+            total_shares,
+            holders,
+            upgrader: owner,
+        };
+        save_shared(&env, &shared);
+        events::mine_fractionalized(&env, &shared);
+        Ok(())
+    }
+
+    /// get the share register of a mine
+    pub fn get_shared_mine(env: Env, mine_id: u32) -> Option<SharedMine> {
+        load_shared(&env, mine_id)
+    }
+
+    /// get the shares of a mine held by an address
+    pub fn get_shares(env: Env, mine_id: u32, holder: Address) -> u32 {
+        load_shared(&env, mine_id)
+            .and_then(|shared| shared.holders.get(holder))
+            .unwrap_or(0)
+    }
+
+    /// move shares of a mine to another address, the votes of the sender
+    /// stay as they are and count with the shares still held
+    pub fn transfer_shares(
+        env: Env,
+        mine_id: u32,
+        from: Address,
+        to: Address,
+        amount: u32,
+    ) -> Result<(), SharesError> {
+        from.require_auth();
+        let mut shared = load_shared(&env, mine_id).ok_or(SharesError::NotShared)?;
+
+        if amount == 0 || from == to {
+            return Err(SharesError::InvalidAmount);
+        }
+        let held = shared.holders.get(from.clone()).unwrap_or(0);
+        if held < amount {
+            return Err(SharesError::InsufficientShares);
+        }
+        if !shared.holders.contains_key(to.clone()) && shared.holders.len() >= MAX_SHAREHOLDERS {
+            return Err(SharesError::TooManyHolders);
+        }
+
+        if held == amount {
+            shared.holders.remove(from.clone());
+            remove_vote(&env, mine_id, &from);
+        } else {
+            shared.holders.set(from.clone(), held - amount);
+        }
+        let received = shared.holders.get(to.clone()).unwrap_or(0);
+        shared.holders.set(to.clone(), received + amount);
+
+        save_shared(&env, &shared);
+        events::shares_transferred(&env, mine_id, &from, &to, amount);
+        Ok(())
+    }
+
+    /// vote for the address allowed to upgrade a shared mine, it takes over
+    /// once holders of more than half the shares back it
+    pub fn vote_upgrader(env: Env, holder: Address, mine_id: u32, candidate: Address) -> Result<(), SharesError> {
+        holder.require_auth();
+        let mut shared = load_shared(&env, mine_id).ok_or(SharesError::NotShared)?;
+        if !shared.holders.contains_key(holder.clone()) {
+            return Err(SharesError::InsufficientShares);
+        }
+
+        ttl::set_persistent(
+            &env,
+            &StorageKey::Shares(DataKey::Vote(mine_id, holder.clone())),
+            &candidate,
+        );
+        events::upgrader_voted(&env, mine_id, &holder, &candidate);
+
+        // tally with the shares held right now
+        let mut support: u32 = 0;
+        for (voter, shares) in shared.holders.iter() {
+            if load_vote(&env, mine_id, &voter) == Some(candidate.clone()) {
+                support += shares;
+            }
+        }
+        if support as u64 * 2 > shared.total_shares as u64 && shared.upgrader != candidate {
+            shared.upgrader = candidate.clone();
+            save_shared(&env, &shared);
+            events::upgrader_elected(&env, mine_id, &candidate, support);
+        }
+        Ok(())
+    }
+
+    /// get harvested metal waiting to be claimed by a shareholder
+    pub fn get_claimable_output(env: Env, holder: Address, metal_type: MetalType) -> i128 {
+        claimable(&env, &holder, &metal_type)
+    }
+
+    /// claim harvested metal credited to a shareholder, returns the amount
+    pub fn claim_output(env: Env, holder: Address, metal_type: MetalType) -> Result<i128, SharesError> {
+        holder.require_auth();
+
+        let amount = claimable(&env, &holder, &metal_type);
+        if amount == 0 {
+            return Err(SharesError::NothingToClaim);
+        }
+        set_claimable(&env, &holder, &metal_type, 0);
+        token::move_balance(&env, &metal_type, &env.current_contract_address(), &holder, amount)
+            .map_err(|_| SharesError::TransferFailed)?;
+
+        events::output_claimed(&env, &holder, &metal_type, amount);
+        Ok(amount)
+    }
+
+    /// join the shares of a mine back together, only an address holding
+    /// every share can redeem it and becomes the sole owner
+    pub fn redeem_mine(env: Env, holder: Address, mine_id: u32) -> Result<(), SharesError> {
+        holder.require_auth();
+        let shared = load_shared(&env, mine_id).ok_or(SharesError::NotShared)?;
+        if shared.holders.get(holder.clone()).unwrap_or(0) < shared.total_shares {
+            return Err(SharesError::InsufficientShares);
+        }
+        if !mining::is_registered(&env, &holder).map_err(|_| SharesError::TransferFailed)? {
+            return Err(SharesError::NotRegistered);
+        }
+
+        // output made while shared still goes to the shareholders
+        let mut mine = mining::load_mine(&env, mine_id).ok_or(SharesError::MineNotFound)?;
+        mining::settle_output(&env, &mut mine).map_err(|_| SharesError::TransferFailed)?;
+
+        env.storage()
+            .persistent()
+            .remove(&StorageKey::Shares(DataKey::Shared(mine_id)));
+        remove_vote(&env, mine_id, &holder);
+        mining::unlock_mine(&env, mine_id);
+        mining::change_owner(&env, &mut mine, &holder).map_err(|_| SharesError::TransferFailed)?;
+        events::mine_redeemed(&env, mine_id, &holder);
+        Ok(())
+    }
+}
@@ -0,0 +1,577 @@
+#![cfg(test)]
+extern crate std;
+
+use soroban_sdk::testutils::{Address as _, Ledger};
+use soroban_sdk::token::{StellarAssetClient, TokenClient};
+use soroban_sdk::{Address, Env, String, Vec};
+
+use crate::auction::AuctionKind;
+use crate::delegation::DelegationScope;
+use crate::equipment::{Blueprint, EquipmentKind, Shop};
+use crate::leaderboard::{LeaderboardEntry, LeaderboardKind, LEADERBOARD_SIZE};
+use crate::marketplace::MarketConfig;
+use crate::metals::Metal;
+use crate::mining::{self, is_rich_vein, roll_mine_stats, HarvestStatus, MetalType};
+use crate::{
+    AccessError, AuctionError, DelegationError, EquipmentError, GameConfig, GameContract,
+    GameContractClient, LeaseError, MarketError, MiningError, Role, SharesError,
+};
+
+const HOUR: u64 = 60 * 60;
+
+// a player and a mining instance linked to each other, the tests drive the
+// mining instance and read player records from the player instance
+struct Game<'a> {
+    env: Env,
+    admin: Address,
+    player: GameContractClient<'a>,
+    mining: GameContractClient<'a>,
+}
+
+fn setup<'a>() -> Game<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let player = GameContractClient::new(&env, &env.register(GameContract, (admin.clone(),)));
+    let mining = GameContractClient::new(&env, &env.register(GameContract, (admin.clone(),)));
+    player.grant_role(&admin, &mining.address, &Role::GameOperator);
+    mining.set_player_contract(&admin, &player.address);
+    Game { env, admin, player, mining }
+}
+
+impl Game<'_> {
+    fn register(&self, name: &str) -> Address {
+        let address = Address::generate(&self.env);
+        self.player.register_player(&address, &String::from_str(&self.env, name));
+        address
+    }
+
+    // a fresh payment token with `amount` minted to each holder
+    fn token(&self, holders: &[&Address], amount: i128) -> Address {
+        let token = self.env.register_stellar_asset_contract_v2(self.admin.clone()).address();
+        for holder in holders {
+            StellarAssetClient::new(&self.env, &token).mint(holder, &amount);
+        }
+        token
+    }
+
+    fn set_market(&self, payment_token: &Address, treasury: &Address, fee_bps: u32) {
+        let config = MarketConfig { payment_token: payment_token.clone(), treasury: treasury.clone(), fee_bps };
+        self.mining.set_market_config(&self.admin, &config);
+    }
+
+    fn advance(&self, seconds: u64) {
+        self.env.ledger().with_mut(|ledger| ledger.timestamp += seconds);
+    }
+}
+
+#[test]
+fn test_mine_stats_replay_from_seed() {
+    let game = setup();
+    let owner = game.register("owner");
+
+    for metal_type in [MetalType::Gold, MetalType::Iron, MetalType::Platinum] {
+        let metal = game.mining.get_metal(&metal_type).unwrap();
+        let mine = game.mining.get_mine(&game.mining.create_mine(&owner, &metal_type)).unwrap();
+
+        let roll = roll_mine_stats(&metal, mine.seed);
+        assert_eq!(roll.efficiency, mine.efficiency);
+        assert_eq!(roll.capacity, mine.capacity);
+        assert_eq!(roll.reserve, mine.initial_reserve);
+        assert_eq!(roll_mine_stats(&metal, mine.seed), roll);
+    }
+}
+
+#[test]
+fn test_rich_vein_doubles_output() {
+    assert!(is_rich_vein(0));
+    assert!(is_rich_vein(101));
+    assert!(!is_rich_vein(2));
+
+    let game = setup();
+    let owner = game.register("owner");
+    let mine_id = game.mining.create_mine(&owner, &MetalType::Iron);
+
+    // one hour per harvest, so every plain harvest yields the same amount
+    let mut plain = None;
+    let mut rich = None;
+    for _ in 0..200 {
+        game.advance(HOUR);
+        let mined = game.mining.harvest_mine(&mine_id);
+        assert_eq!(mined.rich_vein, is_rich_vein(mined.roll));
+        if mined.rich_vein {
+            rich = Some(mined.amount);
+        } else {
+            plain = Some(mined.amount);
+        }
+    }
+    assert_eq!(rich.unwrap(), 2 * plain.unwrap());
+}
+
+#[test]
+fn test_listing_escrow_and_terms() {
+    let game = setup();
+    let seller = game.register("seller");
+    let buyer = game.register("buyer");
+    let stranger = Address::generate(&game.env);
+    let treasury = Address::generate(&game.env);
+    let token = game.token(&[&buyer], 1_000);
+    game.set_market(&token, &treasury, 250);
+
+    // metal leaves the seller while listed and comes back on cancel
+    let mine_id = game.mining.create_mine(&seller, &MetalType::Iron);
+    game.advance(10 * HOUR);
+    game.mining.harvest_mine(&mine_id);
+    let balance = game.mining.balance(&MetalType::Iron, &seller);
+    let metal_listing = game.mining.list_metal(&seller, &MetalType::Iron, &100, &50);
+    assert_eq!(game.mining.balance(&MetalType::Iron, &seller), balance - 100);
+    game.mining.cancel_listing(&metal_listing);
+    assert_eq!(game.mining.balance(&MetalType::Iron, &seller), balance);
+
+    // an escrowed mine stays put, only registered players can buy it, and a
+    // config change after listing does not touch the agreed terms
+    let listing = game.mining.list_mine(&mine_id, &400);
+    assert!(game.mining.try_transfer_mine(&mine_id, &buyer).is_err());
+    assert_eq!(game.mining.try_buy_listing(&stranger, &listing), Err(Ok(MarketError::NotRegistered)));
+    game.set_market(&game.token(&[&buyer], 1_000), &treasury, 0);
+    game.mining.buy_listing(&buyer, &listing);
+
+    let paid = TokenClient::new(&game.env, &token);
+    assert_eq!(paid.balance(&seller), 390);
+    assert_eq!(paid.balance(&treasury), 10);
+    assert_eq!(game.mining.get_mine(&mine_id).unwrap().owner, buyer);
+    assert_eq!(game.mining.get_mine_lock(&mine_id), None);
+    assert_eq!(game.mining.get_listings(&0, &10).len(), 0);
+}
+
+#[test]
+fn test_outbid_refunds_are_claimed() {
+    let game = setup();
+    let seller = game.register("seller");
+    let first = game.register("first");
+    let second = game.register("second");
+    let treasury = Address::generate(&game.env);
+    let token = game.token(&[&first, &second], 1_000);
+    game.set_market(&token, &treasury, 250);
+
+    let mine_id = game.mining.create_mine(&seller, &MetalType::Iron);
+    let end_time = game.env.ledger().timestamp() + 1_000;
+    let auction_id = game.mining.start_auction(&mine_id, &AuctionKind::English, &0, &100, &end_time);
+    game.set_market(&game.token(&[], 0), &treasury, 0);
+
+    // the outbid amount waits in the contract until its bidder claims it
+    let paid = TokenClient::new(&game.env, &token);
+    game.mining.place_bid(&first, &auction_id, &100);
+    game.mining.place_bid(&second, &auction_id, &200);
+    assert_eq!(paid.balance(&first), 900);
+    assert_eq!(game.mining.get_refund(&first, &token), 100);
+    assert_eq!(game.mining.claim_refund(&first, &token), 100);
+    assert_eq!(paid.balance(&first), 1_000);
+    assert_eq!(game.mining.try_claim_refund(&first, &token), Err(Ok(AuctionError::NothingToClaim)));
+
+    game.advance(1_000);
+    game.mining.settle_auction(&auction_id);
+    assert_eq!(paid.balance(&seller), 195);
+    assert_eq!(paid.balance(&treasury), 5);
+    assert_eq!(paid.balance(&second), 800);
+    assert_eq!(game.mining.get_mine(&mine_id).unwrap().owner, second);
+    assert_eq!(game.mining.get_auctions(&1, &10).len(), 0);
+}
+
+#[test]
+fn test_shared_mine_custody_and_redeem() {
+    let game = setup();
+    let owner = game.register("owner");
+    let holder = game.register("holder");
+    let stranger = Address::generate(&game.env);
+
+    let mine_id = game.mining.create_mine(&owner, &MetalType::Iron);
+    game.mining.fractionalize_mine(&mine_id, &2);
+    assert_eq!(game.mining.get_mine(&mine_id).unwrap().owner, game.mining.address);
+    assert_eq!(game.mining.get_player_mines(&owner).len(), 0);
+    assert_eq!(game.player.get_player(&owner).unwrap().active_mines.len(), 0);
+
+    // output goes to the holders in proportion to their shares
+    game.mining.transfer_shares(&mine_id, &owner, &holder, &1);
+    game.advance(2 * HOUR);
+    let mined = game.mining.harvest_mine(&mine_id);
+    let owner_part = game.mining.get_claimable_output(&owner, &MetalType::Iron);
+    let holder_part = game.mining.get_claimable_output(&holder, &MetalType::Iron);
+    assert_eq!((owner_part + holder_part) as u64, mined.amount);
+    let holder_record = game.player.get_player(&holder).unwrap();
+    assert_eq!(holder_record.total_mined, holder_part as u64);
+    assert_eq!(holder_record.experience, holder_part as u64);
+    assert_eq!(game.player.get_player(&owner).unwrap().total_mined, owner_part as u64);
+    assert_eq!(game.mining.claim_output(&holder, &MetalType::Iron), holder_part);
+    assert_eq!(game.mining.balance(&MetalType::Iron, &holder), holder_part);
+    assert_eq!(game.mining.try_claim_output(&holder, &MetalType::Iron), Err(Ok(SharesError::NothingToClaim)));
+
+    // redeeming takes every share and a player record
+    assert_eq!(game.mining.try_redeem_mine(&holder, &mine_id), Err(Ok(SharesError::InsufficientShares)));
+    game.mining.transfer_shares(&mine_id, &owner, &stranger, &1);
+    game.mining.transfer_shares(&mine_id, &holder, &stranger, &1);
+    assert_eq!(game.mining.try_redeem_mine(&stranger, &mine_id), Err(Ok(SharesError::NotRegistered)));
+    game.mining.transfer_shares(&mine_id, &stranger, &holder, &2);
+    game.mining.redeem_mine(&holder, &mine_id);
+    assert_eq!(game.mining.get_mine(&mine_id).unwrap().owner, holder);
+    assert_eq!(game.mining.get_mine_lock(&mine_id), None);
+    assert_eq!(game.player.get_player(&holder).unwrap().active_mines.len(), 1);
+}
+
+#[test]
+fn test_metal_at_registry_bounds() {
+    let game = setup();
+    let owner = game.register("owner");
+    let metal = Metal {
+        id: MetalType::Custom(1),
+        name: String::from_str(&game.env, "Mithril"),
+        symbol: String::from_str(&game.env, "MITH"),
+        base_efficiency: 100,
+        base_capacity: 1_000_000_000,
+        base_rate: 1_000_000,
+        base_reserve: 1_000_000_000_000,
+        upgrade_cost: 1_000_000_000_000,
+        rarity: 1_000,
+        max_upgrade_level: None,
+    };
+    game.mining.add_metal(&game.admin, &metal);
+
+    // rolls reach both sides of the base, not only the low end of the range
+    let rolls: std::vec::Vec<_> = (0..100).map(|seed| roll_mine_stats(&metal, seed)).collect();
+    assert!(rolls.iter().all(|roll| (800_000_000..=1_200_000_000).contains(&roll.capacity)));
+    assert!(rolls.iter().all(|roll| (750_000_000_000..=1_250_000_000_000).contains(&roll.reserve)));
+    assert!(rolls.iter().any(|roll| roll.capacity > metal.base_capacity));
+    assert!(rolls.iter().any(|roll| roll.reserve > metal.base_reserve));
+
+    // a full stockpile on a nearly empty mine still scales down without overflow
+    let mine_id = game.mining.create_mine(&owner, &metal.id);
+    game.env.as_contract(&game.mining.address, || {
+        let mut mine = mining::load_mine(&game.env, mine_id).unwrap();
+        mine.reserve = mine.initial_reserve / 5 - 1;
+        mining::save_mine(&game.env, &mine);
+    });
+    game.advance(2_000 * HOUR);
+    let mine = game.mining.get_mine(&mine_id).unwrap();
+    let pending = game.mining.pending_output(&mine_id);
+    assert!(pending > 0 && pending < mine.capacity);
+
+    let mined = game.mining.harvest_mine(&mine_id);
+    assert!(mined.amount >= pending);
+    assert_eq!(game.mining.get_remaining_reserve(&mine_id), mine.reserve - mined.amount);
+}
+
+#[test]
+fn test_single_instance_game() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    let game = GameContractClient::new(&env, &env.register(GameContract, (admin.clone(),)));
+    game.set_player_contract(&admin, &game.address);
+
+    let owner = Address::generate(&env);
+    game.register_player(&owner, &String::from_str(&env, "owner"));
+    let mine_id = game.create_mine(&owner, &MetalType::Iron);
+    env.ledger().with_mut(|ledger| ledger.timestamp += 2 * HOUR);
+    let mined = game.harvest_mine(&mine_id);
+
+    let player = game.get_player(&owner).unwrap();
+    assert_eq!(player.active_mines.len(), 1);
+    assert_eq!(player.total_mined, mined.amount);
+    assert_eq!(player.experience, mined.amount);
+    assert_eq!(game.balance(&MetalType::Iron, &owner) as u64, mined.amount);
+}
+
+#[test]
+fn test_equipment_bonus_bounds() {
+    let game = setup();
+    let owner = game.register("owner");
+    let token = game.token(&[&owner], 10);
+    let shop = Shop { token, treasury: Address::generate(&game.env) };
+    game.mining.set_equipment_shop(&game.admin, &shop);
+
+    let mut blueprint = Blueprint {
+        id: 0,
+        name: String::from_str(&game.env, "Drill"),
+        kind: EquipmentKind::Drill,
+        efficiency_bonus_bps: 10_001,
+        capacity_bonus_bps: 10_000,
+        max_durability: 5,
+        craft_cost: Vec::new(&game.env),
+        price: 1,
+    };
+    assert_eq!(game.mining.try_add_blueprint(&game.admin, &blueprint), Err(Ok(EquipmentError::InvalidBlueprint)));
+    blueprint.efficiency_bonus_bps = 10_000;
+    let blueprint_id = game.mining.add_blueprint(&game.admin, &blueprint);
+
+    // a full set of maxed items quadruples the stats and harvests keep working
+    let mine_id = game.mining.create_mine(&owner, &MetalType::Iron);
+    for _ in 0..3 {
+        let equipment_id = game.mining.buy_equipment(&owner, &blueprint_id);
+        game.mining.equip(&mine_id, &equipment_id);
+    }
+    game.advance(2 * HOUR);
+    let mined = game.mining.harvest_mine(&mine_id);
+    assert_eq!(mined.efficiency_bonus, 4 * game.mining.get_mine(&mine_id).unwrap().efficiency);
+}
+
+// output of `hours` full hours of a level 1 mine
+fn hourly_output(game: &Game, mine_id: u32, hours: u64) -> u64 {
+    let mine = game.mining.get_mine(&mine_id).unwrap();
+    let rate = game.mining.get_metal(&mine.metal_type).unwrap().base_rate;
+    rate * hours * mine.efficiency as u64 / 100
+}
+
+// a rich vein doubles what pending_output showed
+fn expected_harvest(pending: u64, rich_vein: bool) -> u64 {
+    if rich_vein {
+        2 * pending
+    } else {
+        pending
+    }
+}
+
+#[test]
+fn test_accrual_carries_minutes_and_caps_at_capacity() {
+    let game = setup();
+    let owner = game.register("owner");
+    let mine_id = game.mining.create_mine(&owner, &MetalType::Iron);
+
+    // 90 minutes credit one hour, the pending amount is what the harvest pays
+    game.advance(90 * 60);
+    let pending = game.mining.pending_output(&mine_id);
+    assert_eq!(pending, hourly_output(&game, mine_id, 1));
+    let mined = game.mining.harvest_mine(&mine_id);
+    assert_eq!(mined.hours, 1);
+    assert_eq!(mined.amount, expected_harvest(pending, mined.rich_vein));
+
+    // the 30 minutes left over count towards the next hours
+    game.advance(90 * 60);
+    let pending = game.mining.pending_output(&mine_id);
+    assert_eq!(pending, hourly_output(&game, mine_id, 2));
+    let mined = game.mining.harvest_mine(&mine_id);
+    assert_eq!(mined.hours, 2);
+    assert_eq!(mined.amount, expected_harvest(pending, mined.rich_vein));
+
+    // a long wait fills the stockpile up to the capacity and no further
+    game.advance(1_000 * HOUR);
+    let pending = game.mining.pending_output(&mine_id);
+    assert_eq!(pending, game.mining.get_mine(&mine_id).unwrap().capacity);
+    let mined = game.mining.harvest_mine(&mine_id);
+    assert_eq!(mined.amount, expected_harvest(pending, mined.rich_vein));
+}
+
+#[test]
+fn test_lease_output_split_at_end_time() {
+    let game = setup();
+    let owner = game.register("owner");
+    let lessee = game.register("lessee");
+    let mine_id = game.mining.create_mine(&owner, &MetalType::Iron);
+
+    // the lease runs out after 2 of the 5 hours before the next harvest
+    game.mining.lease_mine(&mine_id, &lessee, &(2 * HOUR), &2_500);
+    game.advance(5 * HOUR);
+    let pending = game.mining.pending_output(&mine_id);
+    assert_eq!(pending, hourly_output(&game, mine_id, 5));
+    let mined = game.mining.harvest_mine(&mine_id);
+    assert_eq!(mined.amount, expected_harvest(pending, mined.rich_vein));
+
+    // the lease hours are split by the revenue share, the rest is the owner's
+    let lease_stock = hourly_output(&game, mine_id, 2);
+    let lease_amount = mined.amount * lease_stock / pending;
+    let owner_cut = lease_amount * 2_500 / 10_000;
+    let owner_balance = game.mining.balance(&MetalType::Iron, &owner) as u64;
+    let lessee_balance = game.mining.balance(&MetalType::Iron, &lessee) as u64;
+    assert_eq!(lessee_balance, lease_amount - owner_cut);
+    assert_eq!(owner_balance, owner_cut + mined.amount - lease_amount);
+    assert_eq!(game.mining.get_lease(&mine_id), None);
+    assert_eq!(game.mining.get_mine_lock(&mine_id), None);
+}
+
+#[test]
+fn test_lease_lifecycle() {
+    let game = setup();
+    let owner = game.register("owner");
+    let lessee = game.register("lessee");
+    let stranger = Address::generate(&game.env);
+    let mine_id = game.mining.create_mine(&owner, &MetalType::Iron);
+
+    assert_eq!(game.mining.try_lease_mine(&mine_id, &stranger, &HOUR, &0), Err(Ok(LeaseError::NotRegistered)));
+    game.mining.lease_mine(&mine_id, &lessee, &(10 * HOUR), &2_500);
+    assert_eq!(game.mining.try_lease_mine(&mine_id, &lessee, &HOUR, &0), Err(Ok(LeaseError::MineLocked)));
+    assert_eq!(game.mining.try_transfer_mine(&mine_id, &lessee), Err(Ok(MiningError::MineLocked)));
+
+    // the owner keeps the revenue share of every harvest during the lease
+    game.advance(2 * HOUR);
+    let mined = game.mining.harvest_mine(&mine_id);
+    let owner_cut = mined.amount * 2_500 / 10_000;
+    assert_eq!(game.mining.balance(&MetalType::Iron, &owner) as u64, owner_cut);
+    assert_eq!(game.mining.balance(&MetalType::Iron, &lessee) as u64, mined.amount - owner_cut);
+
+    // ending early hands the mine and its output back to the owner
+    game.mining.end_lease(&mine_id);
+    assert_eq!(game.mining.get_lease(&mine_id), None);
+    assert_eq!(game.mining.get_mine_lock(&mine_id), None);
+    game.advance(2 * HOUR);
+    let lessee_balance = game.mining.balance(&MetalType::Iron, &lessee);
+    game.mining.harvest_mine(&mine_id);
+    assert_eq!(game.mining.balance(&MetalType::Iron, &lessee), lessee_balance);
+
+    // a lease that runs out unlocks the mine on its own
+    game.mining.lease_mine(&mine_id, &lessee, &HOUR, &0);
+    game.advance(2 * HOUR);
+    assert_eq!(game.mining.get_lease(&mine_id), None);
+    assert_eq!(game.mining.get_mine_lock(&mine_id), None);
+    game.mining.transfer_mine(&mine_id, &lessee);
+    assert_eq!(game.mining.get_mine(&mine_id).unwrap().owner, lessee);
+}
+
+#[test]
+fn test_batch_harvest_statuses_and_totals() {
+    let game = setup();
+    let owner = game.register("owner");
+    let other = game.register("other");
+    let iron = game.mining.create_mine(&owner, &MetalType::Iron);
+    let gold = game.mining.create_mine(&owner, &MetalType::Gold);
+    let fresh = game.mining.create_mine(&owner, &MetalType::Iron);
+    let empty = game.mining.create_mine(&owner, &MetalType::Iron);
+    let foreign = game.mining.create_mine(&other, &MetalType::Iron);
+    game.env.as_contract(&game.mining.address, || {
+        let mut mine = mining::load_mine(&game.env, empty).unwrap();
+        mine.reserve = 0;
+        mining::save_mine(&game.env, &mine);
+    });
+    game.advance(2 * HOUR);
+    game.mining.harvest_mine(&fresh);
+
+    let ids = Vec::from_array(&game.env, [iron, gold, fresh, empty, foreign, 99]);
+    let batch = game.mining.harvest_many(&owner, &ids);
+    let statuses: std::vec::Vec<_> = batch.results.iter().map(|result| result.status).collect();
+    let HarvestStatus::Harvested(iron_mined) = statuses[0].clone() else { panic!("iron not harvested") };
+    let HarvestStatus::Harvested(gold_mined) = statuses[1].clone() else { panic!("gold not harvested") };
+    assert_eq!(statuses[2], HarvestStatus::TooEarly);
+    assert_eq!(statuses[3], HarvestStatus::Exhausted);
+    assert_eq!(statuses[4], HarvestStatus::NotAllowed);
+    assert_eq!(statuses[5], HarvestStatus::NotFound);
+
+    // one total per metal
+    assert_eq!(batch.totals.len(), 2);
+    assert_eq!(batch.totals.get(MetalType::Iron), Some(iron_mined.amount));
+    assert_eq!(batch.totals.get(MetalType::Gold), Some(gold_mined.amount));
+
+    // harvest_all goes over the owner's own list
+    game.advance(2 * HOUR);
+    let batch = game.mining.harvest_all(&owner);
+    assert_eq!(batch.results.len(), 4);
+    assert_eq!(batch.results.get(3).unwrap().status, HarvestStatus::Exhausted);
+    let harvested = batch.results.iter().filter(|result| matches!(result.status, HarvestStatus::Harvested(_)));
+    assert_eq!(harvested.count(), 3);
+}
+
+#[test]
+fn test_delegation_scope_expiry_and_revoke() {
+    let game = setup();
+    let owner = game.register("owner");
+    let keeper = Address::generate(&game.env);
+    let scoped = game.mining.create_mine(&owner, &MetalType::Iron);
+    let other = game.mining.create_mine(&owner, &MetalType::Iron);
+    let now = game.env.ledger().timestamp();
+
+    let scope = DelegationScope::Mines(Vec::from_array(&game.env, [scoped]));
+    assert_eq!(
+        game.mining.try_grant_harvester(&owner, &owner, &scope, &(now + HOUR)),
+        Err(Ok(DelegationError::InvalidDelegate))
+    );
+    assert_eq!(game.mining.try_grant_harvester(&owner, &keeper, &scope, &now), Err(Ok(DelegationError::InvalidExpiry)));
+
+    // a scoped delegate harvests only the listed mines, for the owner
+    game.mining.grant_harvester(&owner, &keeper, &scope, &(now + 10 * HOUR));
+    game.advance(2 * HOUR);
+    let mined = game.mining.delegated_harvest(&keeper, &scoped);
+    assert_eq!(game.mining.balance(&MetalType::Iron, &owner) as u64, mined.amount);
+    assert_eq!(game.mining.balance(&MetalType::Iron, &keeper), 0);
+    assert_eq!(game.mining.try_delegated_harvest(&keeper, &other), Err(Ok(MiningError::NotDelegated)));
+
+    // revoking takes the permission away at once
+    game.mining.revoke_harvester(&owner, &keeper);
+    game.advance(2 * HOUR);
+    assert_eq!(game.mining.try_delegated_harvest(&keeper, &scoped), Err(Ok(MiningError::NotDelegated)));
+    assert_eq!(game.mining.try_revoke_harvester(&owner, &keeper), Err(Ok(DelegationError::DelegationNotFound)));
+
+    // an expired delegation no longer counts, even for every mine
+    let now = game.env.ledger().timestamp();
+    game.mining.grant_harvester(&owner, &keeper, &DelegationScope::All, &(now + HOUR));
+    game.mining.delegated_harvest(&keeper, &other);
+    game.advance(2 * HOUR);
+    assert_eq!(game.mining.get_delegation(&owner, &keeper), None);
+    assert_eq!(game.mining.try_delegated_harvest(&keeper, &scoped), Err(Ok(MiningError::NotDelegated)));
+}
+
+#[test]
+fn test_leaderboard_order_and_size() {
+    let game = setup();
+    let players: std::vec::Vec<Address> = (0..LEADERBOARD_SIZE + 5).map(|_| game.register("player")).collect();
+    for (score, player) in (1u64..).zip(players.iter()) {
+        game.player.update_total_mined(&game.admin, player, &score);
+    }
+
+    // sorted high to low and cut at LEADERBOARD_SIZE
+    let board = game.player.get_leaderboard(&LeaderboardKind::TotalMined, &0, &(2 * LEADERBOARD_SIZE));
+    assert_eq!(board.len(), LEADERBOARD_SIZE);
+    assert_eq!(board.get(0).unwrap().score, LEADERBOARD_SIZE as u64 + 5);
+    assert_eq!(board.get(LEADERBOARD_SIZE - 1).unwrap().score, 6);
+    assert!((1..board.len()).all(|i| board.get(i - 1).unwrap().score >= board.get(i).unwrap().score));
+    assert_eq!(game.player.get_rank(&players[4]).total_mined, None);
+    assert_eq!(game.player.get_rank(&players[5]).total_mined, Some(LEADERBOARD_SIZE));
+
+    // a climbing player moves up, a tie goes to whoever got there first
+    let runner_up = &players[LEADERBOARD_SIZE as usize + 3];
+    game.player.update_total_mined(&game.admin, &players[0], &(LEADERBOARD_SIZE as u64 + 3));
+    assert_eq!(game.player.get_rank(runner_up).total_mined, Some(2));
+    assert_eq!(game.player.get_rank(&players[0]).total_mined, Some(3));
+    let top = game.player.get_leaderboard(&LeaderboardKind::TotalMined, &0, &3);
+    assert_eq!(top.get(2).unwrap(), LeaderboardEntry { player: players[0].clone(), score: LEADERBOARD_SIZE as u64 + 4 });
+    assert_eq!(game.player.get_rank(&players[5]).total_mined, None);
+    assert_eq!(game.player.get_leaderboard(&LeaderboardKind::TotalMined, &98, &10).len(), 2);
+}
+
+#[test]
+fn test_game_config_bounds() {
+    const DAY: u64 = 24 * HOUR;
+    let game = setup();
+    let set = |config: &GameConfig| game.mining.try_set_game_config(&game.admin, config);
+    let invalid = Err(Ok(AccessError::InvalidGameConfig));
+
+    let edges = [
+        GameConfig { harvest_cooldown: HOUR, ..GameConfig::default() },
+        GameConfig { harvest_cooldown: 7 * DAY, ..GameConfig::default() },
+        GameConfig { max_upgrade_level: 100, ..GameConfig::default() },
+        GameConfig { upgrade_efficiency_bonus: 100, upgrade_capacity_bps: 10_000, ..GameConfig::default() },
+        GameConfig { activity_window: HOUR, ..GameConfig::default() },
+        GameConfig { activity_window: 30 * DAY, ..GameConfig::default() },
+    ];
+    for config in edges.iter() {
+        assert_eq!(set(config), Ok(Ok(())));
+    }
+
+    let out_of_bounds = [
+        GameConfig { harvest_cooldown: HOUR - 1, ..GameConfig::default() },
+        GameConfig { harvest_cooldown: 7 * DAY + 1, ..GameConfig::default() },
+        GameConfig { max_upgrade_level: 0, ..GameConfig::default() },
+        GameConfig { max_upgrade_level: 101, ..GameConfig::default() },
+        GameConfig { upgrade_efficiency_bonus: 101, ..GameConfig::default() },
+        GameConfig { upgrade_capacity_bps: 10_001, ..GameConfig::default() },
+        GameConfig { experience_per_level: 0, ..GameConfig::default() },
+        GameConfig { activity_window: HOUR - 1, ..GameConfig::default() },
+        GameConfig { activity_window: 30 * DAY + 1, ..GameConfig::default() },
+    ];
+    for config in out_of_bounds.iter() {
+        assert_eq!(set(config), invalid);
+    }
+
+    // metals without their own cap follow the game's max_upgrade_level
+    set(&GameConfig { max_upgrade_level: 1, ..GameConfig::default() }).unwrap().unwrap();
+    let owner = game.register("owner");
+    let mine_id = game.mining.create_mine(&owner, &MetalType::Iron);
+    assert_eq!(game.mining.try_upgrade_mine(&mine_id), Err(Ok(MiningError::MaxUpgradeLevel)));
+}
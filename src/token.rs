@@ -0,0 +1,242 @@
+use soroban_sdk::{contractimpl, contracttype, Address, Env, String};
+
+use crate::errors::TokenError;
+use crate::events;
+use crate::metals;
+use crate::mining::MetalType;
+use crate::ttl;
+use crate::{GameContract, GameContractArgs, GameContractClient, StorageKey};
+
+// Every metal is its own fungible asset. The entry points follow the SEP-41
+// token interface with an extra `metal_type` argument picking the asset.
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AllowanceValue {
+    pub amount: i128,
+    pub expiration_ledger: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DataKey {
+    Balance(MetalType, Address),
+    Allowance(MetalType, Address, Address), // metal, owner, spender
+    TotalSupply(MetalType),
+}
+
+fn check_amount(amount: i128) -> Result<(), TokenError> {
+    if amount < 0 {
+        return Err(TokenError::NegativeAmount);
+    }
+    Ok(())
+}
+
+fn read_balance(env: &Env, metal_type: &MetalType, owner: &Address) -> i128 {
+    ttl::get_persistent(env, &StorageKey::Token(DataKey::Balance(metal_type.clone(), owner.clone())))
+        .unwrap_or(0)
+}
+
+fn write_balance(env: &Env, metal_type: &MetalType, owner: &Address, amount: i128) {
+    ttl::set_persistent(
+        env,
+        &StorageKey::Token(DataKey::Balance(metal_type.clone(), owner.clone())),
+        &amount,
+    );
+}
+
+fn read_total_supply(env: &Env, metal_type: &MetalType) -> i128 {
+    ttl::get_instance(env, &StorageKey::Token(DataKey::TotalSupply(metal_type.clone()))).unwrap_or(0)
+}
+
+fn write_total_supply(env: &Env, metal_type: &MetalType, amount: i128) {
+    ttl::set_instance(env, &StorageKey::Token(DataKey::TotalSupply(metal_type.clone())), &amount);
+}
+
+// allowances live in temporary storage and simply disappear once expired
+fn read_allowance(env: &Env, metal_type: &MetalType, owner: &Address, spender: &Address) -> AllowanceValue {
+    let key = StorageKey::Token(DataKey::Allowance(metal_type.clone(), owner.clone(), spender.clone()));
+    match env.storage().temporary().get::<_, AllowanceValue>(&key) {
+        Some(allowance) if allowance.expiration_ledger >= env.ledger().sequence() => allowance,
+        _ => AllowanceValue { amount: 0, expiration_ledger: 0 },
+    }
+}
+
+fn write_allowance(
+    env: &Env,
+    metal_type: &MetalType,
+    owner: &Address,
+    spender: &Address,
+    amount: i128,
+    expiration_ledger: u32,
+) -> Result<(), TokenError> {
+    if amount > 0 && expiration_ledger < env.ledger().sequence() {
+        return Err(TokenError::InvalidExpiration);
+    }
+
+    let key = StorageKey::Token(DataKey::Allowance(metal_type.clone(), owner.clone(), spender.clone()));
+    env.storage()
+        .temporary()
+        .set(&key, &AllowanceValue { amount, expiration_ledger });
+    if amount > 0 {
+        let live_for = expiration_ledger - env.ledger().sequence();
+        env.storage().temporary().extend_ttl(&key, live_for, live_for);
+    }
+    Ok(())
+}
+
+fn spend_allowance(
+    env: &Env,
+    metal_type: &MetalType,
+    owner: &Address,
+    spender: &Address,
+    amount: i128,
+) -> Result<(), TokenError> {
+    let allowance = read_allowance(env, metal_type, owner, spender);
+    if allowance.amount < amount {
+        return Err(TokenError::InsufficientAllowance);
+    }
+    if amount > 0 {
+        write_allowance(
+            env,
+            metal_type,
+            owner,
+            spender,
+            allowance.amount - amount,
+            allowance.expiration_ledger,
+        )?;
+    }
+    Ok(())
+}
+
+fn spend_balance(env: &Env, metal_type: &MetalType, owner: &Address, amount: i128) -> Result<(), TokenError> {
+    let balance = read_balance(env, metal_type, owner);
+    if balance < amount {
+        return Err(TokenError::InsufficientBalance);
+    }
+    write_balance(env, metal_type, owner, balance - amount);
+    Ok(())
+}
+
+pub(crate) fn move_balance(env: &Env, metal_type: &MetalType, from: &Address, to: &Address, amount: i128) -> Result<(), TokenError> {
+    spend_balance(env, metal_type, from, amount)?;
+    write_balance(env, metal_type, to, read_balance(env, metal_type, to) + amount);
+    events::metal_transferred(env, metal_type, from, to, amount);
+    Ok(())
+}
+
+pub(crate) fn balance(env: &Env, metal_type: &MetalType, owner: &Address) -> i128 {
+    read_balance(env, metal_type, owner)
+}
+
+/// credit freshly produced metal to `to`
+pub(crate) fn mint(env: &Env, metal_type: &MetalType, to: &Address, amount: i128) {
+    write_balance(env, metal_type, to, read_balance(env, metal_type, to) + amount);
+    write_total_supply(env, metal_type, read_total_supply(env, metal_type) + amount);
+    events::metal_minted(env, metal_type, to, amount);
+}
+
+/// destroy metal held by `from`, used when metal is consumed by the game
+pub(crate) fn burn(env: &Env, metal_type: &MetalType, from: &Address, amount: i128) -> Result<(), TokenError> {
+    spend_balance(env, metal_type, from, amount)?;
+    write_total_supply(env, metal_type, read_total_supply(env, metal_type) - amount);
+    events::metal_burned(env, metal_type, from, amount);
+    Ok(())
+}
+
+#[contractimpl]
+impl GameContract {
+    /// get allowance of spender over owner's metal
+    pub fn allowance(env: Env, metal_type: MetalType, from: Address, spender: Address) -> i128 {
+        read_allowance(&env, &metal_type, &from, &spender).amount
+    }
+
+    /// allow spender to move up to amount of from's metal until expiration_ledger
+    pub fn approve(
+        env: Env,
+        metal_type: MetalType,
+        from: Address,
+        spender: Address,
+        amount: i128,
+        expiration_ledger: u32,
+    ) -> Result<(), TokenError> {
+        from.require_auth();
+        check_amount(amount)?;
+
+        write_allowance(&env, &metal_type, &from, &spender, amount, expiration_ledger)?;
+        events::metal_approved(&env, &metal_type, &from, &spender, amount, expiration_ledger);
+        Ok(())
+    }
+
+    /// get metal balance
+    pub fn balance(env: Env, metal_type: MetalType, id: Address) -> i128 {
+        read_balance(&env, &metal_type, &id)
+    }
+
+    /// get total amount of a metal in circulation
+    pub fn total_supply(env: Env, metal_type: MetalType) -> i128 {
+        read_total_supply(&env, &metal_type)
+    }
+
+    /// send metal
+    pub fn transfer(env: Env, metal_type: MetalType, from: Address, to: Address, amount: i128) -> Result<(), TokenError> {
+        from.require_auth();
+        check_amount(amount)?;
+
+        move_balance(&env, &metal_type, &from, &to, amount)
+    }
+
+    /// send metal on behalf of from using an allowance
+    pub fn transfer_from(
+        env: Env,
+        metal_type: MetalType,
+        spender: Address,
+        from: Address,
+        to: Address,
+        amount: i128,
+    ) -> Result<(), TokenError> {
+        spender.require_auth();
+        check_amount(amount)?;
+
+        spend_allowance(&env, &metal_type, &from, &spender, amount)?;
+        move_balance(&env, &metal_type, &from, &to, amount)
+    }
+
+    /// destroy own metal
+    pub fn burn(env: Env, metal_type: MetalType, from: Address, amount: i128) -> Result<(), TokenError> {
+        from.require_auth();
+        check_amount(amount)?;
+
+        burn(&env, &metal_type, &from, amount)
+    }
+
+    /// destroy metal on behalf of from using an allowance
+    pub fn burn_from(
+        env: Env,
+        metal_type: MetalType,
+        spender: Address,
+        from: Address,
+        amount: i128,
+    ) -> Result<(), TokenError> {
+        spender.require_auth();
+        check_amount(amount)?;
+
+        spend_allowance(&env, &metal_type, &from, &spender, amount)?;
+        burn(&env, &metal_type, &from, amount)
+    }
+
+    /// metals are counted in whole units
+    pub fn decimals(_env: Env) -> u32 {
+        0
+    }
+
+    /// get metal name
+    pub fn name(env: Env, metal_type: MetalType) -> String {
+        metals::require(&env, &metal_type).name
+    }
+
+    /// get metal symbol
+    pub fn symbol(env: Env, metal_type: MetalType) -> String {
+        metals::require(&env, &metal_type).symbol
+    }
+}
@@ -0,0 +1,111 @@
+use soroban_sdk::{contracttype, Env, IntoVal, TryFromVal, Val};
+
+use crate::StorageKey;
+
+// ledgers close roughly every 5 seconds
+pub const DAY_IN_LEDGERS: u32 = 17_280;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TtlConfig {
+    pub instance_threshold: u32,   // bump instance once its ttl drops below this
+    pub instance_extend_to: u32,   // new instance ttl after a bump
+    pub persistent_threshold: u32, // same for per-player and per-mine entries
+    pub persistent_extend_to: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DataKey {
+    Config,
+}
+
+impl Default for TtlConfig {
+    fn default() -> Self {
+        TtlConfig {
+            instance_threshold: 6 * DAY_IN_LEDGERS,
+            instance_extend_to: 7 * DAY_IN_LEDGERS,
+            persistent_threshold: 29 * DAY_IN_LEDGERS,
+            persistent_extend_to: 30 * DAY_IN_LEDGERS,
+        }
+    }
+}
+
+impl TtlConfig {
+    pub fn is_valid(&self, env: &Env) -> bool {
+        let max_ttl = env.storage().max_ttl();
+        self.instance_threshold < self.instance_extend_to
+            && self.persistent_threshold < self.persistent_extend_to
+            && self.instance_extend_to <= max_ttl
+            && self.persistent_extend_to <= max_ttl
+    }
+}
+
+// read without a bump, every bump reads the config first
+pub(crate) fn config(env: &Env) -> TtlConfig {
+    env.storage()
+        .instance()
+        .get(&StorageKey::Ttl(DataKey::Config))
+        .unwrap_or_default()
+}
+
+pub(crate) fn set_config(env: &Env, config: &TtlConfig) {
+    set_instance(env, &StorageKey::Ttl(DataKey::Config), config);
+}
+
+fn extend_instance(env: &Env) {
+    let config = config(env);
+    env.storage()
+        .instance()
+        .extend_ttl(config.instance_threshold, config.instance_extend_to);
+}
+
+fn extend_persistent(env: &Env, key: &StorageKey) {
+    let config = config(env);
+    env.storage()
+        .persistent()
+        .extend_ttl(key, config.persistent_threshold, config.persistent_extend_to);
+}
+
+// settings and counters live in instance storage, which is bumped on every
+// access. Keys are passed with their module namespace, e.g.
+// `StorageKey::Mining(DataKey::MineCount)`
+
+pub(crate) fn has_instance(env: &Env, key: &StorageKey) -> bool {
+    extend_instance(env);
+    env.storage().instance().has(key)
+}
+
+pub(crate) fn get_instance<V: TryFromVal<Env, Val>>(env: &Env, key: &StorageKey) -> Option<V> {
+    extend_instance(env);
+    env.storage().instance().get(key)
+}
+
+pub(crate) fn set_instance<V: IntoVal<Env, Val>>(env: &Env, key: &StorageKey, value: &V) {
+    env.storage().instance().set(key, value);
+    extend_instance(env);
+}
+
+pub(crate) fn remove_instance(env: &Env, key: &StorageKey) {
+    env.storage().instance().remove(key);
+    extend_instance(env);
+}
+
+// persistent entries are bumped whenever they are touched
+
+pub(crate) fn has_persistent(env: &Env, key: &StorageKey) -> bool {
+    env.storage().persistent().has(key)
+}
+
+pub(crate) fn get_persistent<V: TryFromVal<Env, Val>>(env: &Env, key: &StorageKey) -> Option<V> {
+    let value = env.storage().persistent().get(key);
+    if value.is_some() {
+        extend_persistent(env, key);
+    }
+    value
+}
+
+pub(crate) fn set_persistent<V: IntoVal<Env, Val>>(env: &Env, key: &StorageKey, value: &V) {
+    env.storage().persistent().set(key, value);
+    extend_persistent(env, key);
+}